@@ -0,0 +1,170 @@
+//! M-of-N admin council logic shared by both programs. Each program owns
+//! its `AdminCouncil`/`CouncilProposal` accounts and signs as the council PDA
+//! `[b"council", create_key]`; the checks and instruction building live here
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+
+pub const MAX_MEMBERS: usize = 10;
+pub const MAX_DATA_LEN: usize = 512;
+pub const MAX_ACCOUNTS: usize = 16;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct CouncilAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl CouncilAccountMeta {
+    pub const LEN: usize = 32 + 1 + 1;
+}
+
+pub fn validate_council(members: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(members.len() <= MAX_MEMBERS, CouncilError::TooManyMembers);
+    require!(
+        threshold > 0 && threshold as usize <= members.len(),
+        CouncilError::InvalidThreshold
+    );
+    for (i, member) in members.iter().enumerate() {
+        require!(!members[..i].contains(member), CouncilError::DuplicateMember);
+    }
+    Ok(())
+}
+
+pub fn validate_proposal(instruction_data: &[u8], accounts: &[CouncilAccountMeta]) -> Result<()> {
+    require!(instruction_data.len() <= MAX_DATA_LEN, CouncilError::ProposalTooLarge);
+    require!(accounts.len() <= MAX_ACCOUNTS, CouncilError::ProposalTooLarge);
+    Ok(())
+}
+
+/// Add `member`'s approval, returning the new approval count
+pub fn approve(approvals: &mut Vec<Pubkey>, member: Pubkey) -> Result<u8> {
+    require!(!approvals.contains(&member), CouncilError::AlreadyApproved);
+    approvals.push(member);
+    Ok(approvals.len() as u8)
+}
+
+/// Count only approvals from current members, in case membership changed
+pub fn check_threshold(approvals: &[Pubkey], members: &[Pubkey], threshold: u8) -> Result<()> {
+    let current = approvals
+        .iter()
+        .filter(|approver| members.contains(approver))
+        .count();
+    require!(current >= threshold as usize, CouncilError::ThresholdNotMet);
+    Ok(())
+}
+
+/// Build the proposed instruction, checking `remaining_accounts` match its metas in order
+pub fn proposal_instruction(
+    program_id: Pubkey,
+    accounts: &[CouncilAccountMeta],
+    instruction_data: &[u8],
+    remaining_accounts: &[AccountInfo],
+) -> Result<Instruction> {
+    require!(
+        remaining_accounts.len() == accounts.len(),
+        CouncilError::ProposalAccountMismatch
+    );
+    let mut metas = Vec::with_capacity(accounts.len());
+    for (meta, info) in accounts.iter().zip(remaining_accounts) {
+        require_keys_eq!(meta.pubkey, info.key(), CouncilError::ProposalAccountMismatch);
+        metas.push(if meta.is_writable {
+            AccountMeta::new(meta.pubkey, meta.is_signer)
+        } else {
+            AccountMeta::new_readonly(meta.pubkey, meta.is_signer)
+        });
+    }
+    Ok(Instruction {
+        program_id,
+        accounts: metas,
+        data: instruction_data.to_vec(),
+    })
+}
+
+#[error_code(offset = 7000)]
+pub enum CouncilError {
+    #[msg("Signer is not a council member")]
+    NotCouncilMember,
+
+    #[msg("Council has too many members")]
+    TooManyMembers,
+
+    #[msg("Duplicate council member")]
+    DuplicateMember,
+
+    #[msg("Invalid council threshold")]
+    InvalidThreshold,
+
+    #[msg("Council proposal too large")]
+    ProposalTooLarge,
+
+    #[msg("Member already approved this proposal")]
+    AlreadyApproved,
+
+    #[msg("Council approval threshold not met")]
+    ThresholdNotMet,
+
+    #[msg("Remaining accounts do not match the proposal")]
+    ProposalAccountMismatch,
+
+    #[msg("Only the proposer can cancel a proposal")]
+    NotProposer,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(n: usize) -> Vec<Pubkey> {
+        (0..n).map(|_| Pubkey::new_unique()).collect()
+    }
+
+    #[test]
+    fn threshold_counts_current_members_only() {
+        let members = members(3);
+        let mut approvals = vec![members[0]];
+        assert!(check_threshold(&approvals, &members, 2).is_err());
+
+        approve(&mut approvals, members[1]).unwrap();
+        assert!(check_threshold(&approvals, &members, 2).is_ok());
+
+        // A removed member's approval no longer counts
+        let rotated = vec![members[0], members[2]];
+        assert!(check_threshold(&approvals, &rotated, 2).is_err());
+        approve(&mut approvals, members[2]).unwrap();
+        assert!(check_threshold(&approvals, &rotated, 2).is_ok());
+    }
+
+    #[test]
+    fn approve_rejects_duplicates() {
+        let member = Pubkey::new_unique();
+        let mut approvals = vec![];
+        assert_eq!(approve(&mut approvals, member).unwrap(), 1);
+        assert!(approve(&mut approvals, member).is_err());
+        assert_eq!(approvals.len(), 1);
+    }
+
+    #[test]
+    fn validate_council_bounds() {
+        let five = members(5);
+        assert!(validate_council(&five, 1).is_ok());
+        assert!(validate_council(&five, 5).is_ok());
+        assert!(validate_council(&five, 0).is_err());
+        assert!(validate_council(&five, 6).is_err());
+        assert!(validate_council(&members(MAX_MEMBERS + 1), 1).is_err());
+        assert!(validate_council(&[five[0], five[1], five[0]], 2).is_err());
+    }
+
+    #[test]
+    fn validate_proposal_limits() {
+        let meta = CouncilAccountMeta {
+            pubkey: Pubkey::new_unique(),
+            is_signer: false,
+            is_writable: true,
+        };
+        assert!(validate_proposal(&[0; MAX_DATA_LEN], &vec![meta.clone(); MAX_ACCOUNTS]).is_ok());
+        assert!(validate_proposal(&[0; MAX_DATA_LEN + 1], &[]).is_err());
+        assert!(validate_proposal(&[], &vec![meta; MAX_ACCOUNTS + 1]).is_err());
+    }
+}
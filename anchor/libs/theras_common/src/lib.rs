@@ -0,0 +1,4 @@
+//! Code shared by the `theras_protocol` and `tgem_plus` programs
+
+pub mod council;
+pub mod migration;
@@ -0,0 +1,42 @@
+//! Helpers for upgrading legacy accounts in place
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+/// Decode a legacy account body after checking its discriminator
+pub fn read_legacy_account<T: AnchorDeserialize>(
+    info: &AccountInfo,
+    discriminator: &[u8],
+) -> Result<T> {
+    let data = info.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && data[..8] == *discriminator,
+        anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+    );
+    T::deserialize(&mut &data[8..])
+        .map_err(|_| error!(anchor_lang::error::ErrorCode::AccountDidNotDeserialize))
+}
+
+/// Grow `account` to `new_len`, topping up rent from `payer`
+pub fn realloc_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    let shortfall = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if shortfall > 0 {
+        let cpi_ctx = CpiContext::new(
+            system_program.clone(),
+            system_program::Transfer {
+                from: payer.clone(),
+                to: account.clone(),
+            },
+        );
+        system_program::transfer(cpi_ctx, shortfall)?;
+    }
+    account.realloc(new_len, true)?;
+    Ok(())
+}
@@ -1,7 +1,20 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::keccak;
+use anchor_lang::Discriminator;
 use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+use theras_common::council::{self, CouncilAccountMeta, CouncilError};
+use theras_common::migration::{read_legacy_account, realloc_account};
+use theras_protocol::Project;
 
-declare_id!("waBySt7ASB6QkHK7X8zjoeAsYRWC5zgnbJwsA9vNKcGL");
+declare_id!("waBySt7ASB6QkHK7X8zjoeAsYRWC5zgnbJwsA9vNKcG");
+
+/// Delay applied to queued admin changes until reconfigured (48 hours)
+pub const DEFAULT_TIMELOCK_DELAY: i64 = 172_800;
+pub const MAX_TIMELOCK_DELAY: i64 = 2_592_000;
+pub const MAX_REDEMPTION_FEE_BPS: u16 = 1_000;
+/// How long a change stays executable once its delay has elapsed (7 days)
+pub const CHANGE_EXECUTION_WINDOW: i64 = 604_800;
 
 #[program]
 pub mod tgem_plus {
@@ -14,28 +27,74 @@ pub mod tgem_plus {
     ) -> Result<()> {
         let state = &mut ctx.accounts.state;
         state.authority = ctx.accounts.authority.key();
+        state.pending_authority = None;
         state.reflect_vault = reflect_vault;
         state.total_supply = 0;
         state.total_collateral = 0;
-        state.paused = false;
+        state.guardian = ctx.accounts.authority.key();
+        state.mint_paused = false;
+        state.catalog_redemption_paused = false;
+        state.stablecoin_redemption_paused = false;
         state.bump = ctx.bumps.state;
+        state.shutdown = false;
+        state.shutdown_at = 0;
+        state.shutdown_supply = 0;
+        state.shutdown_assets = 0;
+        state.timelock_delay = DEFAULT_TIMELOCK_DELAY;
+        state.change_count = 0;
+        state.version = TGEMPlusState::VERSION;
+        state.collateral_mint = ctx.accounts.collateral_mint.key();
+        state.catalog_redeemed = 0;
+        state.stablecoin_redeemed = 0;
+        state.migration_liabilities = 0;
+        state.yield_paused = false;
+        state.supply_cap = 0;
+        state.redemption_fee_bps = 0;
+        state.reserved = [0; TGEMPlusState::RESERVED_LEN];
         
         msg!("TGEM+ initialized with Reflect vault: {}", reflect_vault);
         Ok(())
     }
 
-    /// Mint TGEM+ tokens (called by Theras backend after collateral deposit)
+    /// Create the collateral sub-ledger for a theras_protocol project
+    pub fn initialize_project_ledger(ctx: Context<InitializeProjectLedger>) -> Result<()> {
+        let ledger = &mut ctx.accounts.project_ledger;
+        ledger.project = ctx.accounts.project.key();
+        ledger.funded_collateral = 0;
+        ledger.released_collateral = 0;
+        ledger.minted_supply = 0;
+        ledger.bump = ctx.bumps.project_ledger;
+
+        msg!("Project ledger initialized for: {}", ledger.project);
+        Ok(())
+    }
+
+    /// Mint TGEM+ tokens, depositing the same amount of collateral into the
+    /// vault in the same instruction (called by Theras backend)
     pub fn mint_tgem_plus(
         ctx: Context<MintTGEMPlus>,
         amount: u64,
     ) -> Result<()> {
         let state = &ctx.accounts.state;
-        require!(!state.paused, ErrorCode::ProgramPaused);
+        require!(!state.is_paused(PausableOperation::Mint), ErrorCode::OperationPaused);
+        require!(!state.shutdown, ErrorCode::ProtocolShutdown);
         require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(state.within_supply_cap(amount), ErrorCode::SupplyCapExceeded);
+
+        // Back the new supply 1:1 before it exists
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.authority_collateral_account.to_account_info(),
+                to: ctx.accounts.collateral_vault.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, amount)?;
 
         // Mint tokens to user
         let seeds = &[
-            b"state",
+            b"state".as_ref(),
             &[state.bump],
         ];
         let signer = &[&seeds[..]];
@@ -59,8 +118,13 @@ pub mod tgem_plus {
         state.total_supply = state.total_supply.checked_add(amount).unwrap();
         state.total_collateral = state.total_collateral.checked_add(amount).unwrap();
 
+        let ledger = &mut ctx.accounts.project_ledger;
+        ledger.funded_collateral = ledger.funded_collateral.checked_add(amount).unwrap();
+        ledger.minted_supply = ledger.minted_supply.checked_add(amount).unwrap();
+
         emit!(MintEvent {
             user: ctx.accounts.user_wallet.key(),
+            project: ctx.accounts.project.key(),
             amount,
             timestamp: Clock::get()?.unix_timestamp,
         });
@@ -76,7 +140,8 @@ pub mod tgem_plus {
         amount: u64,
     ) -> Result<()> {
         let state = &ctx.accounts.state;
-        require!(!state.paused, ErrorCode::ProgramPaused);
+        require!(!state.is_paused(PausableOperation::CatalogRedemption), ErrorCode::OperationPaused);
+        require!(!state.shutdown, ErrorCode::ProtocolShutdown);
         require!(amount > 0, ErrorCode::InvalidAmount);
         require!(item_id.len() <= 64, ErrorCode::ItemIdTooLong);
 
@@ -97,12 +162,14 @@ pub mod tgem_plus {
         // Update state
         let state = &mut ctx.accounts.state;
         state.total_supply = state.total_supply.checked_sub(amount).unwrap();
+        state.catalog_redeemed = state.catalog_redeemed.checked_add(amount).unwrap();
 
         emit!(RedemptionEvent {
             user: ctx.accounts.user.key(),
             redemption_type: RedemptionType::Catalog,
             item_id: Some(item_id),
             amount,
+            collateral_released: 0,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
@@ -115,10 +182,33 @@ pub mod tgem_plus {
         ctx: Context<RedeemStablecoin>,
         amount: u64,
     ) -> Result<()> {
+        // A vault backing less than outstanding supply can no longer honour 1:1
+        let backing = ctx.accounts.state.backing_assets(ctx.accounts.collateral_vault.amount);
+        if !ctx.accounts.state.shutdown && backing < ctx.accounts.state.total_supply {
+            let event = enter_shutdown(
+                &mut ctx.accounts.state,
+                ShutdownReason::ReservesCheck,
+                backing,
+                Some(ctx.accounts.user.key()),
+            )?;
+            emit_cpi!(event);
+        }
+
+        // Pausing stops new activity, never the pro-rata exit after shutdown
         let state = &ctx.accounts.state;
-        require!(!state.paused, ErrorCode::ProgramPaused);
+        require!(
+            state.shutdown || !state.is_paused(PausableOperation::StablecoinRedemption),
+            ErrorCode::OperationPaused
+        );
         require!(amount > 0, ErrorCode::InvalidAmount);
 
+        let payout = if state.shutdown {
+            state.pro_rata_payout(amount, backing)
+        } else {
+            amount.checked_sub(state.redemption_fee(amount)).unwrap()
+        };
+        require!(payout > 0, ErrorCode::InvalidAmount);
+
         // Burn TGEM+ tokens
         let cpi_accounts = Burn {
             mint: ctx.accounts.mint.to_account_info(),
@@ -135,7 +225,7 @@ pub mod tgem_plus {
 
         // Transfer collateral (USDC) from vault to user
         let seeds = &[
-            b"state",
+            b"state".as_ref(),
             &[state.bump],
         ];
         let signer = &[&seeds[..]];
@@ -152,58 +242,659 @@ pub mod tgem_plus {
             signer,
         );
 
-        token::transfer(cpi_ctx, amount)?;
+        token::transfer(cpi_ctx, payout)?;
 
         // Update state
         let state = &mut ctx.accounts.state;
         state.total_supply = state.total_supply.checked_sub(amount).unwrap();
-        state.total_collateral = state.total_collateral.checked_sub(amount).unwrap();
+        state.total_collateral = state.total_collateral.checked_sub(payout).unwrap();
+        state.stablecoin_redeemed = state.stablecoin_redeemed.checked_add(amount).unwrap();
 
         emit!(RedemptionEvent {
             user: ctx.accounts.user.key(),
             redemption_type: RedemptionType::Stablecoin,
             item_id: None,
             amount,
+            collateral_released: payout,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Redeemed {} TGEM+ for {} stablecoin", amount, payout);
+        Ok(())
+    }
+
+    /// Irreversibly shut down the protocol (admin only)
+    pub fn trigger_shutdown(ctx: Context<Shutdown>) -> Result<()> {
+        require!(!ctx.accounts.state.shutdown, ErrorCode::ProtocolShutdown);
+
+        let backing = ctx.accounts.state.backing_assets(ctx.accounts.collateral_vault.amount);
+        let event = enter_shutdown(
+            &mut ctx.accounts.state,
+            ShutdownReason::Authority,
+            backing,
+            Some(ctx.accounts.authority.key()),
+        )?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Compare the vault's backing assets against outstanding supply, shutting
+    /// down on a shortfall (permissionless)
+    pub fn check_reserves(ctx: Context<CheckReserves>) -> Result<()> {
+        let state = &ctx.accounts.state;
+        let backing = state.backing_assets(ctx.accounts.collateral_vault.amount);
+
+        if state.shutdown || backing >= state.total_supply {
+            msg!("Reserves check: {} assets for {} supply", backing, state.total_supply);
+            return Ok(());
+        }
+
+        let event = enter_shutdown(
+            &mut ctx.accounts.state,
+            ShutdownReason::ReservesCheck,
+            backing,
+            None,
+        )?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Sweep vault assets above outstanding supply, i.e. Reflect yield and
+    /// retained redemption fees, to `destination` (admin only)
+    pub fn harvest_yield(ctx: Context<HarvestYield>) -> Result<()> {
+        let state = &ctx.accounts.state;
+        require!(!state.is_paused(PausableOperation::Yield), ErrorCode::OperationPaused);
+        require!(!state.shutdown, ErrorCode::ProtocolShutdown);
+
+        let surplus = state
+            .backing_assets(ctx.accounts.collateral_vault.amount)
+            .saturating_sub(state.total_supply);
+        require!(surplus > 0, ErrorCode::NoYield);
+
+        let seeds: &[&[u8]] = &[b"state", &[state.bump]];
+        let signer = &[seeds];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.collateral_vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.state.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+
+        token::transfer(cpi_ctx, surplus)?;
+
+        // Only the part of the surplus that was booked as collateral leaves
+        // `total_collateral`; yield earned in the vault was never in it
+        let state = &mut ctx.accounts.state;
+        let booked_surplus = state
+            .total_collateral
+            .saturating_sub(state.total_supply)
+            .saturating_sub(state.migration_liabilities);
+        state.total_collateral = state.total_collateral.checked_sub(surplus.min(booked_surplus)).unwrap();
+
+        emit_cpi!(YieldHarvested {
+            amount: surplus,
+            destination: ctx.accounts.destination.key(),
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Harvested {} yield", surplus);
+        Ok(())
+    }
+
+    /// Create a Merkle distributor converting a project's off-chain TGEM to TGEM+
+    /// (funds the collateral for every leaf up front)
+    pub fn create_migration_distributor(
+        ctx: Context<CreateMigrationDistributor>,
+        merkle_root: [u8; 32],
+        num_leaves: u32,
+        collateral_amount: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        require!(
+            num_leaves > 0 && num_leaves <= MigrationDistributor::MAX_LEAVES,
+            ErrorCode::InvalidLeafCount
+        );
+        require!(collateral_amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            deadline > Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidDeadline
+        );
+
+        // Deposit collateral backing every migration claim
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.authority_collateral_account.to_account_info(),
+            to: ctx.accounts.collateral_vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+
+        token::transfer(cpi_ctx, collateral_amount)?;
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.project = ctx.accounts.project.key();
+        distributor.merkle_root = merkle_root;
+        distributor.num_leaves = num_leaves;
+        distributor.collateral_funded = collateral_amount;
+        distributor.total_claimed = 0;
+        distributor.claimed_count = 0;
+        distributor.deadline = deadline;
+        distributor.reclaimed = false;
+        distributor.bump = ctx.bumps.distributor;
+        distributor.claimed_bitmap = vec![0; MigrationDistributor::bitmap_len(num_leaves)];
+
+        let state = &mut ctx.accounts.state;
+        state.total_collateral = state.total_collateral.checked_add(collateral_amount).unwrap();
+        state.migration_liabilities = state.migration_liabilities.checked_add(collateral_amount).unwrap();
+
+        let ledger = &mut ctx.accounts.project_ledger;
+        ledger.funded_collateral = ledger.funded_collateral.checked_add(collateral_amount).unwrap();
+
+        msg!(
+            "Migration distributor created for {} ({} leaves, {} collateral)",
+            distributor.project,
+            num_leaves,
+            collateral_amount
+        );
+        Ok(())
+    }
+
+    /// Claim migrated TGEM+ with a Merkle proof of (index, wallet, amount)
+    pub fn claim_migration(
+        ctx: Context<ClaimMigration>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let state = &ctx.accounts.state;
+        require!(!state.is_paused(PausableOperation::Mint), ErrorCode::OperationPaused);
+        require!(!state.shutdown, ErrorCode::ProtocolShutdown);
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(state.within_supply_cap(amount), ErrorCode::SupplyCapExceeded);
+
+        let distributor = &mut ctx.accounts.distributor;
+        require!(
+            Clock::get()?.unix_timestamp <= distributor.deadline,
+            ErrorCode::MigrationExpired
+        );
+        require!(index < distributor.num_leaves, ErrorCode::InvalidLeafIndex);
+        require!(!distributor.is_claimed(index), ErrorCode::AlreadyClaimed);
+
+        let leaf = migration_leaf(index, &ctx.accounts.claimant.key(), amount);
+        require!(
+            verify_merkle_proof(&proof, distributor.merkle_root, leaf),
+            ErrorCode::InvalidMerkleProof
+        );
+
+        let total_claimed = distributor.total_claimed.checked_add(amount).unwrap();
+        require!(
+            total_claimed <= distributor.collateral_funded,
+            ErrorCode::InsufficientCollateral
+        );
+        distributor.total_claimed = total_claimed;
+        distributor.claimed_count = distributor.claimed_count.saturating_add(1);
+        distributor.set_claimed(index);
+
+        // Mint TGEM+ 1:1 against the pre-funded collateral
+        let seeds: &[&[u8]] = &[b"state", &[state.bump]];
+        let signer = &[seeds];
+
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.claimant_token_account.to_account_info(),
+            authority: ctx.accounts.state.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+
+        token::mint_to(cpi_ctx, amount)?;
+
+        let state = &mut ctx.accounts.state;
+        state.total_supply = state.total_supply.checked_add(amount).unwrap();
+        state.migration_liabilities = state.migration_liabilities.checked_sub(amount).unwrap();
+
+        let ledger = &mut ctx.accounts.project_ledger;
+        ledger.minted_supply = ledger.minted_supply.checked_add(amount).unwrap();
+
+        emit!(MigrationClaimed {
+            user: ctx.accounts.claimant.key(),
+            project: ledger.project,
+            index,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Migrated {} TGEM to TGEM+ for {}", amount, ctx.accounts.claimant.key());
+        Ok(())
+    }
+
+    /// Return unclaimed migration collateral to the authority after the deadline,
+    /// or at any time after shutdown since claims are closed from then on.
+    /// Unclaimed collateral never backs supply, so redeemers don't share it
+    pub fn reclaim_migration_collateral(ctx: Context<ReclaimMigrationCollateral>) -> Result<()> {
+        let distributor = &mut ctx.accounts.distributor;
+        require!(
+            ctx.accounts.state.shutdown || Clock::get()?.unix_timestamp > distributor.deadline,
+            ErrorCode::MigrationStillActive
+        );
+        require!(!distributor.reclaimed, ErrorCode::AlreadyReclaimed);
+
+        let unclaimed = distributor
+            .collateral_funded
+            .checked_sub(distributor.total_claimed)
+            .unwrap();
+        distributor.reclaimed = true;
+
+        // A vault that lost funds pays out what it still holds
+        let returned = unclaimed.min(ctx.accounts.collateral_vault.amount);
+        if returned > 0 {
+            let state = &ctx.accounts.state;
+            let seeds: &[&[u8]] = &[b"state", &[state.bump]];
+            let signer = &[seeds];
+
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.collateral_vault.to_account_info(),
+                to: ctx.accounts.authority_collateral_account.to_account_info(),
+                authority: ctx.accounts.state.to_account_info(),
+            };
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+
+            token::transfer(cpi_ctx, returned)?;
+        }
+
+        let state = &mut ctx.accounts.state;
+        state.total_collateral = state.total_collateral.saturating_sub(returned);
+        state.migration_liabilities = state.migration_liabilities.checked_sub(unclaimed).unwrap();
+
+        let ledger = &mut ctx.accounts.project_ledger;
+        ledger.released_collateral = ledger.released_collateral.checked_add(returned).unwrap();
+
+        emit_cpi!(MigrationCollateralReclaimed {
+            project: ledger.project,
+            amount: returned,
+            actor: ctx.accounts.authority.key(),
             timestamp: Clock::get()?.unix_timestamp,
         });
 
-        msg!("Redeemed {} TGEM+ for stablecoin", amount);
+        msg!("Reclaimed {} unclaimed migration collateral", returned);
         Ok(())
     }
 
-    /// Update Reflect vault address (admin only)
+    /// Queue a sensitive admin change behind the timelock (admin only)
+    pub fn queue_change(
+        ctx: Context<QueueChange>,
+        change: AdminChange,
+    ) -> Result<()> {
+        queue_admin_change(ctx, change)
+    }
+
+    /// Queue a new Reflect vault address (admin only, timelocked)
     pub fn update_reflect_vault(
-        ctx: Context<UpdateConfig>,
+        ctx: Context<QueueChange>,
         new_vault: Pubkey,
     ) -> Result<()> {
+        queue_admin_change(ctx, AdminChange::ReflectVault { new_vault })
+    }
+
+    /// Queue a handover to `new_authority`, who must then call `accept_authority`
+    /// (admin only, timelocked)
+    pub fn transfer_authority(
+        ctx: Context<QueueChange>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        queue_admin_change(ctx, AdminChange::Authority { new_authority })
+    }
+
+    /// Queue a new pause-only guardian (admin only, timelocked)
+    pub fn set_guardian(
+        ctx: Context<QueueChange>,
+        new_guardian: Pubkey,
+    ) -> Result<()> {
+        queue_admin_change(ctx, AdminChange::Guardian { new_guardian })
+    }
+
+    /// Apply a queued change once its delay has elapsed (permissionless)
+    pub fn execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let pending = &ctx.accounts.pending_change;
+        pending.check_executable(now)?;
+
         let state = &mut ctx.accounts.state;
-        state.reflect_vault = new_vault;
-        
-        msg!("Updated Reflect vault to: {}", new_vault);
+        let previous = pending.change.current(state);
+        match pending.change {
+            AdminChange::ReflectVault { new_vault } => {
+                let old_vault = state.reflect_vault;
+                state.reflect_vault = new_vault;
+
+                emit_cpi!(VaultUpdated {
+                    old_vault,
+                    new_vault,
+                    actor: ctx.accounts.executor.key(),
+                    timestamp: now,
+                });
+            }
+            AdminChange::Authority { new_authority } => {
+                state.pending_authority = Some(new_authority)
+            }
+            AdminChange::Guardian { new_guardian } => {
+                let old_guardian = state.guardian;
+                state.guardian = new_guardian;
+
+                emit_cpi!(GuardianUpdated {
+                    old_guardian,
+                    new_guardian,
+                    actor: ctx.accounts.executor.key(),
+                    timestamp: now,
+                });
+            }
+            AdminChange::RedemptionFee { fee_bps } => state.redemption_fee_bps = fee_bps,
+            AdminChange::SupplyCap { cap } => state.supply_cap = cap,
+            AdminChange::TimelockDelay { delay } => state.timelock_delay = delay,
+        }
+
+        emit_cpi!(ChangeExecuted {
+            id: pending.id,
+            previous,
+            change: pending.change.clone(),
+            executor: ctx.accounts.executor.key(),
+            timestamp: now,
+        });
+
+        msg!("Executed admin change {}", pending.id);
         Ok(())
     }
 
-    /// Pause/unpause program (emergency only)
+    /// Drop a queued or expired change (admin or guardian)
+    pub fn cancel_change(ctx: Context<CancelChange>) -> Result<()> {
+        let pending = &ctx.accounts.pending_change;
+
+        emit_cpi!(ChangeCancelled {
+            id: pending.id,
+            change: pending.change.clone(),
+            canceller: ctx.accounts.canceller.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Cancelled admin change {}", pending.id);
+        Ok(())
+    }
+
+    /// Pause/unpause a single operation (admin only)
     pub fn set_paused(
         ctx: Context<UpdateConfig>,
+        operation: PausableOperation,
         paused: bool,
     ) -> Result<()> {
         let state = &mut ctx.accounts.state;
-        state.paused = paused;
+        let old_paused = state.is_paused(operation);
+        state.set_pause(operation, paused);
+
+        emit_cpi!(PauseChanged {
+            operation,
+            old_paused,
+            new_paused: paused,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
         
-        msg!("Program paused status: {}", paused);
+        msg!("{:?} paused status: {}", operation, paused);
         Ok(())
     }
 
-    /// Transfer authority to new address
-    pub fn transfer_authority(
-        ctx: Context<UpdateConfig>,
-        new_authority: Pubkey,
+    /// Pause a single operation (guardian or admin, emergency only)
+    pub fn guardian_pause(
+        ctx: Context<GuardianPause>,
+        operation: PausableOperation,
     ) -> Result<()> {
         let state = &mut ctx.accounts.state;
-        state.authority = new_authority;
-        
-        msg!("Transferred authority to: {}", new_authority);
+        let old_paused = state.is_paused(operation);
+        state.set_pause(operation, true);
+
+        emit_cpi!(PauseChanged {
+            operation,
+            old_paused,
+            new_paused: true,
+            actor: ctx.accounts.guardian.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("{:?} paused by guardian", operation);
+        Ok(())
+    }
+
+    /// Accept a pending authority transfer (signed by the new authority)
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        let old_authority = state.authority;
+        state.authority = ctx.accounts.new_authority.key();
+        state.pending_authority = None;
+
+        emit_cpi!(AuthorityTransferred {
+            old_authority,
+            new_authority: state.authority,
+            actor: ctx.accounts.new_authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Transferred authority to: {}", state.authority);
+        Ok(())
+    }
+
+    /// Cancel a pending authority transfer
+    pub fn cancel_authority_transfer(ctx: Context<UpdateConfig>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        let pending_authority = state
+            .pending_authority
+            .take()
+            .ok_or(ErrorCode::NoPendingAuthority)?;
+
+        emit_cpi!(AuthorityTransferCancelled {
+            authority: state.authority,
+            pending_authority,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Cancelled authority transfer to: {}", pending_authority);
+        Ok(())
+    }
+
+    /// Create an M-of-N admin council that can hold `authority` roles
+    pub fn create_council(
+        ctx: Context<CreateCouncil>,
+        create_key: Pubkey,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        council::validate_council(&members, threshold)?;
+
+        let council = &mut ctx.accounts.council;
+        council.create_key = create_key;
+        council.members = members;
+        council.threshold = threshold;
+        council.proposal_count = 0;
+        council.bump = ctx.bumps.council;
+
+        emit_cpi!(CouncilUpdated {
+            council: council.key(),
+            members: council.members.clone(),
+            threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Council created: {} ({}-of-{})", council.key(), threshold, council.members.len());
+        Ok(())
+    }
+
+    /// Replace council members and threshold (executed by the council itself)
+    pub fn update_council(
+        ctx: Context<UpdateCouncil>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        council::validate_council(&members, threshold)?;
+
+        let council = &mut ctx.accounts.council;
+        council.members = members;
+        council.threshold = threshold;
+
+        emit_cpi!(CouncilUpdated {
+            council: council.key(),
+            members: council.members.clone(),
+            threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Council updated: {}-of-{}", threshold, council.members.len());
+        Ok(())
+    }
+
+    /// Propose an admin instruction for the council to sign (member only)
+    pub fn propose_council_action(
+        ctx: Context<ProposeCouncilAction>,
+        instruction_data: Vec<u8>,
+        accounts: Vec<CouncilAccountMeta>,
+    ) -> Result<()> {
+        council::validate_proposal(&instruction_data, &accounts)?;
+
+        let council = &mut ctx.accounts.council;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.council = council.key();
+        proposal.index = council.proposal_count;
+        proposal.proposer = ctx.accounts.member.key();
+        proposal.instruction_data = instruction_data;
+        proposal.accounts = accounts;
+        proposal.approvals = vec![ctx.accounts.member.key()];
+        proposal.bump = ctx.bumps.proposal;
+
+        council.proposal_count = council.proposal_count.checked_add(1).unwrap();
+
+        emit_cpi!(CouncilProposalCreated {
+            council: council.key(),
+            proposal: proposal.key(),
+            index: proposal.index,
+            proposer: proposal.proposer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Council proposal {} created", proposal.index);
+        Ok(())
+    }
+
+    /// Approve a pending council proposal (member only)
+    pub fn approve_council_action(ctx: Context<ApproveCouncilAction>) -> Result<()> {
+        let member = ctx.accounts.member.key();
+        let proposal = &mut ctx.accounts.proposal;
+        let approvals = council::approve(&mut proposal.approvals, member)?;
+
+        emit_cpi!(CouncilProposalApproved {
+            council: proposal.council,
+            proposal: proposal.key(),
+            member,
+            approvals,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Council proposal {} approved by {}", proposal.index, member);
+        Ok(())
+    }
+
+    /// Execute an approved proposal, signing as the council, then close it
+    /// to the proposer (member only)
+    pub fn execute_council_action<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteCouncilAction<'info>>,
+    ) -> Result<()> {
+        let council = &ctx.accounts.council;
+        let proposal = &ctx.accounts.proposal;
+        council::check_threshold(&proposal.approvals, &council.members, council.threshold)?;
+
+        let ix = council::proposal_instruction(
+            crate::ID,
+            &proposal.accounts,
+            &proposal.instruction_data,
+            ctx.remaining_accounts,
+        )?;
+        let seeds: &[&[u8]] = &[b"council", council.create_key.as_ref(), &[council.bump]];
+        invoke_signed(&ix, ctx.remaining_accounts, &[seeds])?;
+
+        emit_cpi!(CouncilProposalExecuted {
+            council: council.key(),
+            proposal: proposal.key(),
+            index: proposal.index,
+            executor: ctx.accounts.member.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Council proposal {} executed", proposal.index);
+        Ok(())
+    }
+
+    /// Withdraw a proposal before execution, refunding its rent (proposer only)
+    pub fn cancel_council_action(ctx: Context<CancelCouncilAction>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+
+        emit_cpi!(CouncilProposalCancelled {
+            council: proposal.council,
+            proposal: proposal.key(),
+            index: proposal.index,
+            proposer: proposal.proposer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Council proposal {} cancelled", proposal.index);
+        Ok(())
+    }
+
+    /// Upgrade the baseline `TGEMPlusState` to the current layout,
+    /// growing it to `TGEMPlusState::LEN` (authority only)
+    pub fn migrate_state(ctx: Context<MigrateState>) -> Result<()> {
+        let info = ctx.accounts.state.to_account_info();
+        match info.data_len() {
+            len if len == 8 + TGEMPlusStateV0::LEN => {}
+            len if len == 8 + TGEMPlusState::LEN => return err!(ErrorCode::AlreadyMigrated),
+            _ => return err!(ErrorCode::UnknownLayout),
+        }
+        let legacy: TGEMPlusStateV0 = read_legacy_account(&info, &TGEMPlusState::DISCRIMINATOR)?;
+        require!(
+            legacy.authority == ctx.accounts.authority.key(),
+            ErrorCode::Unauthorized
+        );
+
+        realloc_account(
+            &info,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + TGEMPlusState::LEN,
+        )?;
+        let state = legacy.into_current(ctx.accounts.collateral_mint.key());
+        state.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        emit!(AccountMigrated {
+            account: info.key(),
+            version: TGEMPlusState::VERSION,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("TGEM+ state migrated to v{}", TGEMPlusState::VERSION);
         Ok(())
     }
 }
@@ -229,6 +920,9 @@ pub struct Initialize<'info> {
     )]
     pub mint: Account<'info, Mint>,
     
+    /// Stablecoin (USDC) backing TGEM+; `reflect_vault` must hold this mint
+    pub collateral_mint: Account<'info, Mint>,
+    
     #[account(mut)]
     pub authority: Signer<'info>,
     
@@ -238,27 +932,95 @@ pub struct Initialize<'info> {
 }
 
 #[derive(Accounts)]
-pub struct MintTGEMPlus<'info> {
-    #[account(mut, seeds = [b"state"], bump = state.bump)]
+pub struct InitializeProjectLedger<'info> {
+    #[account(seeds = [b"state"], bump = state.bump)]
     pub state: Account<'info, TGEMPlusState>,
-    
-    #[account(mut, seeds = [b"mint"], bump)]
-    pub mint: Account<'info, Mint>,
-    
+
+    /// theras_protocol project this ledger tracks (owner checked by Account)
+    pub project: Account<'info, Project>,
+
     #[account(
-        init_if_needed,
-        payer = authority,
-        associated_token::mint = mint,
-        associated_token::authority = user_wallet,
+        init,
+        payer = payer,
+        space = 8 + ProjectLedger::LEN,
+        seeds = [b"ledger", project.key().as_ref()],
+        bump
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
-    
-    /// CHECK: User wallet receiving tokens
+    pub project_ledger: Account<'info, ProjectLedger>,
+
+    #[account(constraint = authority.key() == state.authority @ ErrorCode::Unauthorized)]
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintTGEMPlus<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump,
+        constraint = state.version == TGEMPlusState::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub state: Account<'info, TGEMPlusState>,
+    
+    #[account(mut, seeds = [b"mint"], bump)]
+    pub mint: Account<'info, Mint>,
+    
+    /// theras_protocol project the user earned TGEM+ in (owner checked by Account)
+    #[account(
+        constraint = project.tgem_plus_enabled @ ErrorCode::TGEMPlusNotEnabled,
+        constraint = project.version == Project::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub project: Account<'info, Project>,
+    
+    /// User's theras_protocol registration for `project`
+    #[account(
+        seeds = [b"user", project.key().as_ref(), user_wallet.key().as_ref()],
+        bump = theras_user.bump,
+        seeds::program = theras_protocol::ID,
+        constraint = theras_user.project == project.key() @ ErrorCode::UserNotRegistered,
+        constraint = theras_user.wallet == user_wallet.key() @ ErrorCode::UserNotRegistered
+    )]
+    pub theras_user: Account<'info, theras_protocol::User>,
+    
+    #[account(
+        mut,
+        seeds = [b"ledger", project.key().as_ref()],
+        bump = project_ledger.bump
+    )]
+    pub project_ledger: Account<'info, ProjectLedger>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = user_wallet,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: User wallet receiving tokens
     pub user_wallet: AccountInfo<'info>,
     
-    #[account(mut, constraint = authority.key() == state.authority @ ErrorCode::Unauthorized)]
+    #[account(
+        mut,
+        constraint = collateral_vault.key() == state.reflect_vault @ ErrorCode::InvalidCollateralVault,
+        constraint = collateral_vault.mint == state.collateral_mint @ ErrorCode::InvalidCollateralVault
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority_collateral_account: Account<'info, TokenAccount>,
+    
+    #[account(constraint = authority.key() == state.authority @ ErrorCode::Unauthorized)]
     pub authority: Signer<'info>,
     
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -266,7 +1028,12 @@ pub struct MintTGEMPlus<'info> {
 
 #[derive(Accounts)]
 pub struct RedeemCatalog<'info> {
-    #[account(mut, seeds = [b"state"], bump = state.bump)]
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump,
+        constraint = state.version == TGEMPlusState::VERSION @ ErrorCode::AccountNotMigrated
+    )]
     pub state: Account<'info, TGEMPlusState>,
     
     #[account(mut, seeds = [b"mint"], bump)]
@@ -283,9 +1050,15 @@ pub struct RedeemCatalog<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct RedeemStablecoin<'info> {
-    #[account(mut, seeds = [b"state"], bump = state.bump)]
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump,
+        constraint = state.version == TGEMPlusState::VERSION @ ErrorCode::AccountNotMigrated
+    )]
     pub state: Account<'info, TGEMPlusState>,
     
     #[account(mut, seeds = [b"mint"], bump)]
@@ -298,7 +1071,11 @@ pub struct RedeemStablecoin<'info> {
     )]
     pub user_token_account: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = collateral_vault.key() == state.reflect_vault @ ErrorCode::InvalidCollateralVault,
+        constraint = collateral_vault.mint == state.collateral_mint @ ErrorCode::InvalidCollateralVault
+    )]
     pub collateral_vault: Account<'info, TokenAccount>,
     
     #[account(mut)]
@@ -308,65 +1085,1270 @@ pub struct RedeemStablecoin<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct UpdateConfig<'info> {
+pub struct Shutdown<'info> {
     #[account(mut, seeds = [b"state"], bump = state.bump)]
     pub state: Account<'info, TGEMPlusState>,
     
+    #[account(
+        constraint = collateral_vault.key() == state.reflect_vault @ ErrorCode::InvalidCollateralVault,
+        constraint = collateral_vault.mint == state.collateral_mint @ ErrorCode::InvalidCollateralVault
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    
     #[account(constraint = authority.key() == state.authority @ ErrorCode::Unauthorized)]
     pub authority: Signer<'info>,
 }
 
-#[account]
-pub struct TGEMPlusState {
-    pub authority: Pubkey,           // 32
-    pub reflect_vault: Pubkey,       // 32
-    pub total_supply: u64,           // 8
-    pub total_collateral: u64,       // 8
-    pub paused: bool,                // 1
-    pub bump: u8,                    // 1
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CheckReserves<'info> {
+    #[account(mut, seeds = [b"state"], bump = state.bump)]
+    pub state: Account<'info, TGEMPlusState>,
+    
+    #[account(
+        constraint = collateral_vault.key() == state.reflect_vault @ ErrorCode::InvalidCollateralVault,
+        constraint = collateral_vault.mint == state.collateral_mint @ ErrorCode::InvalidCollateralVault
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
 }
 
-impl TGEMPlusState {
-    pub const LEN: usize = 32 + 32 + 8 + 8 + 1 + 1;
+#[event_cpi]
+#[derive(Accounts)]
+pub struct HarvestYield<'info> {
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump,
+        constraint = state.version == TGEMPlusState::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub state: Account<'info, TGEMPlusState>,
+    
+    #[account(
+        mut,
+        constraint = collateral_vault.key() == state.reflect_vault @ ErrorCode::InvalidCollateralVault,
+        constraint = collateral_vault.mint == state.collateral_mint @ ErrorCode::InvalidCollateralVault
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut, constraint = destination.mint == state.collateral_mint @ ErrorCode::InvalidCollateralVault)]
+    pub destination: Account<'info, TokenAccount>,
+    
+    #[account(constraint = authority.key() == state.authority @ ErrorCode::Unauthorized)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
 }
 
-#[event]
-pub struct MintEvent {
-    pub user: Pubkey,
-    pub amount: u64,
-    pub timestamp: i64,
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(merkle_root: [u8; 32], num_leaves: u32)]
+pub struct CreateMigrationDistributor<'info> {
+    #[account(mut, seeds = [b"state"], bump = state.bump)]
+    pub state: Account<'info, TGEMPlusState>,
+    
+    #[account(
+        constraint = project.tgem_plus_enabled @ ErrorCode::TGEMPlusNotEnabled,
+        constraint = project.version == Project::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"ledger", project.key().as_ref()],
+        bump = project_ledger.bump
+    )]
+    pub project_ledger: Account<'info, ProjectLedger>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MigrationDistributor::LEN + MigrationDistributor::bitmap_len(num_leaves),
+        seeds = [b"migration", project.key().as_ref()],
+        bump
+    )]
+    pub distributor: Account<'info, MigrationDistributor>,
+    
+    #[account(
+        mut,
+        constraint = collateral_vault.key() == state.reflect_vault @ ErrorCode::InvalidCollateralVault,
+        constraint = collateral_vault.mint == state.collateral_mint @ ErrorCode::InvalidCollateralVault
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority_collateral_account: Account<'info, TokenAccount>,
+    
+    #[account(constraint = authority.key() == state.authority @ ErrorCode::Unauthorized)]
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
-#[event]
-pub struct RedemptionEvent {
-    pub user: Pubkey,
-    pub redemption_type: RedemptionType,
-    pub item_id: Option<String>,
-    pub amount: u64,
-    pub timestamp: i64,
+#[derive(Accounts)]
+pub struct ClaimMigration<'info> {
+    #[account(mut, seeds = [b"state"], bump = state.bump)]
+    pub state: Account<'info, TGEMPlusState>,
+    
+    #[account(mut, seeds = [b"mint"], bump)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"migration", distributor.project.as_ref()],
+        bump = distributor.bump
+    )]
+    pub distributor: Account<'info, MigrationDistributor>,
+    
+    /// Claims stop if the project has since opted out of TGEM+
+    #[account(
+        address = distributor.project,
+        constraint = project.tgem_plus_enabled @ ErrorCode::TGEMPlusNotEnabled,
+        constraint = project.version == Project::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"ledger", distributor.project.as_ref()],
+        bump = project_ledger.bump
+    )]
+    pub project_ledger: Account<'info, ProjectLedger>,
+    
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        associated_token::mint = mint,
+        associated_token::authority = claimant,
+    )]
+    pub claimant_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum RedemptionType {
-    Catalog,
-    Stablecoin,
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReclaimMigrationCollateral<'info> {
+    #[account(mut, seeds = [b"state"], bump = state.bump)]
+    pub state: Account<'info, TGEMPlusState>,
+    
+    #[account(
+        mut,
+        seeds = [b"migration", distributor.project.as_ref()],
+        bump = distributor.bump
+    )]
+    pub distributor: Account<'info, MigrationDistributor>,
+    
+    #[account(
+        mut,
+        seeds = [b"ledger", distributor.project.as_ref()],
+        bump = project_ledger.bump
+    )]
+    pub project_ledger: Account<'info, ProjectLedger>,
+    
+    #[account(
+        mut,
+        constraint = collateral_vault.key() == state.reflect_vault @ ErrorCode::InvalidCollateralVault,
+        constraint = collateral_vault.mint == state.collateral_mint @ ErrorCode::InvalidCollateralVault
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority_collateral_account: Account<'info, TokenAccount>,
+    
+    #[account(constraint = authority.key() == state.authority @ ErrorCode::Unauthorized)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
 }
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Program is paused")]
-    ProgramPaused,
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(mut, seeds = [b"state"], bump = state.bump)]
+    pub state: Account<'info, TGEMPlusState>,
     
-    #[msg("Invalid amount")]
-    InvalidAmount,
+    #[account(constraint = authority.key() == state.authority @ ErrorCode::Unauthorized)]
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct GuardianPause<'info> {
+    #[account(mut, seeds = [b"state"], bump = state.bump)]
+    pub state: Account<'info, TGEMPlusState>,
     
-    #[msg("Unauthorized")]
-    Unauthorized,
+    #[account(
+        constraint = guardian.key() == state.guardian
+            || guardian.key() == state.authority @ ErrorCode::Unauthorized
+    )]
+    pub guardian: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct QueueChange<'info> {
+    #[account(mut, seeds = [b"state"], bump = state.bump)]
+    pub state: Account<'info, TGEMPlusState>,
     
-    #[msg("Item ID too long (max 64 chars)")]
-    ItemIdTooLong,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PendingChange::LEN,
+        seeds = [b"pending_change", state.change_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
     
-    #[msg("Insufficient collateral")]
-    InsufficientCollateral,
+    #[account(constraint = authority.key() == state.authority @ ErrorCode::Unauthorized)]
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteChange<'info> {
+    #[account(mut, seeds = [b"state"], bump = state.bump)]
+    pub state: Account<'info, TGEMPlusState>,
+    
+    #[account(
+        mut,
+        seeds = [b"pending_change", pending_change.id.to_le_bytes().as_ref()],
+        bump = pending_change.bump,
+        close = rent_payer
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+    
+    /// CHECK: Rent refund destination, pinned to the recorded payer
+    #[account(mut, address = pending_change.rent_payer)]
+    pub rent_payer: AccountInfo<'info>,
+    
+    pub executor: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelChange<'info> {
+    #[account(seeds = [b"state"], bump = state.bump)]
+    pub state: Account<'info, TGEMPlusState>,
+    
+    #[account(
+        mut,
+        seeds = [b"pending_change", pending_change.id.to_le_bytes().as_ref()],
+        bump = pending_change.bump,
+        close = rent_payer
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+    
+    /// CHECK: Rent refund destination, pinned to the recorded payer
+    #[account(mut, address = pending_change.rent_payer)]
+    pub rent_payer: AccountInfo<'info>,
+    
+    #[account(
+        constraint = canceller.key() == state.authority
+            || canceller.key() == state.guardian @ ErrorCode::Unauthorized
+    )]
+    pub canceller: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(mut, seeds = [b"state"], bump = state.bump)]
+    pub state: Account<'info, TGEMPlusState>,
+    
+    #[account(
+        constraint = state.pending_authority == Some(new_authority.key()) @ ErrorCode::Unauthorized
+    )]
+    pub new_authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(create_key: Pubkey)]
+pub struct CreateCouncil<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AdminCouncil::LEN,
+        seeds = [b"council", create_key.as_ref()],
+        bump
+    )]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateCouncil<'info> {
+    /// Must sign via `execute_council_action`
+    #[account(
+        mut,
+        signer,
+        seeds = [b"council", council.create_key.as_ref()],
+        bump = council.bump
+    )]
+    pub council: Account<'info, AdminCouncil>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeCouncilAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"council", council.create_key.as_ref()],
+        bump = council.bump
+    )]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(
+        init,
+        payer = member,
+        space = 8 + CouncilProposal::LEN,
+        seeds = [b"council_proposal", council.key().as_ref(), council.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+    
+    #[account(mut, constraint = council.members.contains(&member.key()) @ CouncilError::NotCouncilMember)]
+    pub member: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ApproveCouncilAction<'info> {
+    #[account(seeds = [b"council", council.create_key.as_ref()], bump = council.bump)]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(
+        mut,
+        seeds = [b"council_proposal", council.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+    
+    #[account(constraint = council.members.contains(&member.key()) @ CouncilError::NotCouncilMember)]
+    pub member: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteCouncilAction<'info> {
+    /// Read-only here: the proposed instruction may itself rewrite the council
+    #[account(seeds = [b"council", council.create_key.as_ref()], bump = council.bump)]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(
+        mut,
+        seeds = [b"council_proposal", council.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        close = proposer
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+    
+    /// CHECK: Rent refund destination, pinned to the proposer who paid it
+    #[account(mut, address = proposal.proposer)]
+    pub proposer: AccountInfo<'info>,
+    
+    #[account(constraint = council.members.contains(&member.key()) @ CouncilError::NotCouncilMember)]
+    pub member: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelCouncilAction<'info> {
+    #[account(seeds = [b"council", council.create_key.as_ref()], bump = council.bump)]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(
+        mut,
+        seeds = [b"council_proposal", council.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        close = proposer
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+    
+    #[account(mut, address = proposal.proposer @ CouncilError::NotProposer)]
+    pub proposer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateState<'info> {
+    /// CHECK: Legacy `TGEMPlusState`; discriminator and length checked in the handler
+    #[account(mut, seeds = [b"state"], bump)]
+    pub state: UncheckedAccount<'info>,
+    
+    /// Stablecoin (USDC) held in the legacy `reflect_vault`
+    pub collateral_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct TGEMPlusState {
+    pub authority: Pubkey,           // 32
+    pub pending_authority: Option<Pubkey>, // 1 + 32
+    pub reflect_vault: Pubkey,       // 32
+    pub total_supply: u64,           // 8
+    pub total_collateral: u64,       // 8
+    pub guardian: Pubkey,            // 32
+    pub mint_paused: bool,           // 1
+    pub catalog_redemption_paused: bool,    // 1
+    pub stablecoin_redemption_paused: bool, // 1
+    pub bump: u8,                    // 1
+    pub shutdown: bool,              // 1
+    pub shutdown_at: i64,            // 8
+    pub shutdown_supply: u64,        // 8
+    pub shutdown_assets: u64,        // 8
+    pub timelock_delay: i64,         // 8
+    pub change_count: u64,           // 8
+    pub version: u8,                 // 1
+    /// Mint of the collateral held in `reflect_vault`
+    pub collateral_mint: Pubkey,     // 32
+    /// Burned for catalog items; redemptions aren't attributed to a project
+    pub catalog_redeemed: u64,       // 8
+    /// Burned for collateral; see `catalog_redeemed`
+    pub stablecoin_redeemed: u64,    // 8
+    /// Unclaimed collateral of open migration distributors, held in the
+    /// vault but not yet backing any supply
+    pub migration_liabilities: u64,  // 8
+    /// Gates `harvest_yield`
+    pub yield_paused: bool,          // 1
+    pub supply_cap: u64,             // 8 (0 = uncapped)
+    pub redemption_fee_bps: u16,     // 2
+    /// Zeroed space for future fields, so adding one doesn't need a realloc
+    pub reserved: [u8; 61],          // 61
+}
+
+impl TGEMPlusState {
+    pub const VERSION: u8 = 1;
+    pub const RESERVED_LEN: usize = 61;
+    pub const LEN: usize = 32 + 33 + 32 + 8 + 8 + 32 + 1 + 1 + 1 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 32 + 8 + 8 + 8 + 1 + 8 + 2 + 61;
+
+    pub fn within_supply_cap(&self, amount: u64) -> bool {
+        self.supply_cap == 0
+            || self
+                .total_supply
+                .checked_add(amount)
+                .is_some_and(|supply| supply <= self.supply_cap)
+    }
+
+    /// Portion of a stablecoin redemption retained in the vault
+    pub fn redemption_fee(&self, amount: u64) -> u64 {
+        ((amount as u128) * (self.redemption_fee_bps as u128) / 10_000) as u64
+    }
+
+    pub fn is_paused(&self, operation: PausableOperation) -> bool {
+        match operation {
+            PausableOperation::Mint => self.mint_paused,
+            PausableOperation::CatalogRedemption => self.catalog_redemption_paused,
+            PausableOperation::StablecoinRedemption => self.stablecoin_redemption_paused,
+            PausableOperation::Yield => self.yield_paused,
+        }
+    }
+
+    pub fn set_pause(&mut self, operation: PausableOperation, paused: bool) {
+        match operation {
+            PausableOperation::Mint => self.mint_paused = paused,
+            PausableOperation::CatalogRedemption => self.catalog_redemption_paused = paused,
+            PausableOperation::StablecoinRedemption => self.stablecoin_redemption_paused = paused,
+            PausableOperation::Yield => self.yield_paused = paused,
+        }
+    }
+
+    /// Vault assets left for TGEM+ holders once migration collateral
+    /// owed to claimants is set aside
+    pub fn backing_assets(&self, vault_assets: u64) -> u64 {
+        vault_assets.saturating_sub(self.migration_liabilities)
+    }
+
+    /// Collateral owed for `amount` TGEM+ when only `backing` remains
+    pub fn pro_rata_payout(&self, amount: u64, backing: u64) -> u64 {
+        if self.total_supply == 0 {
+            return 0;
+        }
+        ((amount as u128) * (backing as u128) / (self.total_supply as u128)) as u64
+    }
+}
+
+/// `TGEMPlusState` as first deployed, decoded by `migrate_state`
+#[derive(AnchorDeserialize)]
+struct TGEMPlusStateV0 {
+    authority: Pubkey,
+    reflect_vault: Pubkey,
+    total_supply: u64,
+    total_collateral: u64,
+    paused: bool,
+    bump: u8,
+}
+
+impl TGEMPlusStateV0 {
+    const LEN: usize = 32 + 32 + 8 + 8 + 1 + 1;
+
+    /// The single `paused` flag carries over to every operation, and the
+    /// authority doubles as guardian until one is appointed
+    fn into_current(self, collateral_mint: Pubkey) -> TGEMPlusState {
+        TGEMPlusState {
+            authority: self.authority,
+            pending_authority: None,
+            reflect_vault: self.reflect_vault,
+            total_supply: self.total_supply,
+            total_collateral: self.total_collateral,
+            guardian: self.authority,
+            mint_paused: self.paused,
+            catalog_redemption_paused: self.paused,
+            stablecoin_redemption_paused: self.paused,
+            bump: self.bump,
+            shutdown: false,
+            shutdown_at: 0,
+            shutdown_supply: 0,
+            shutdown_assets: 0,
+            timelock_delay: DEFAULT_TIMELOCK_DELAY,
+            change_count: 0,
+            version: TGEMPlusState::VERSION,
+            collateral_mint,
+            catalog_redeemed: 0,
+            stablecoin_redeemed: 0,
+            migration_liabilities: 0,
+            yield_paused: self.paused,
+            supply_cap: 0,
+            redemption_fee_bps: 0,
+            reserved: [0; TGEMPlusState::RESERVED_LEN],
+        }
+    }
+}
+
+#[account]
+pub struct AdminCouncil {
+    pub create_key: Pubkey,           // 32
+    pub members: Vec<Pubkey>,         // 4 + 32 * council::MAX_MEMBERS
+    pub threshold: u8,                // 1
+    pub proposal_count: u64,          // 8
+    pub bump: u8,                     // 1
+}
+
+impl AdminCouncil {
+    pub const LEN: usize = 32 + 4 + 32 * council::MAX_MEMBERS + 1 + 8 + 1;
+}
+
+/// Closed to the proposer on execution or cancellation; the index-derived
+/// address is never reused, so an executed proposal can't be replayed
+#[account]
+pub struct CouncilProposal {
+    pub council: Pubkey,                   // 32
+    pub index: u64,                        // 8
+    pub proposer: Pubkey,                  // 32
+    pub instruction_data: Vec<u8>,         // 4 + MAX_DATA_LEN
+    pub accounts: Vec<CouncilAccountMeta>, // 4 + 34 * MAX_ACCOUNTS
+    pub approvals: Vec<Pubkey>,            // 4 + 32 * MAX_MEMBERS
+    pub bump: u8,                          // 1
+}
+
+impl CouncilProposal {
+    pub const LEN: usize = 32 + 8 + 32
+        + 4 + council::MAX_DATA_LEN
+        + 4 + CouncilAccountMeta::LEN * council::MAX_ACCOUNTS
+        + 4 + 32 * council::MAX_MEMBERS
+        + 1;
+}
+
+/// Record `change` as the next `PendingChange`, executable once the delay
+/// has elapsed and until `CHANGE_EXECUTION_WINDOW` after that
+fn queue_admin_change(ctx: Context<QueueChange>, change: AdminChange) -> Result<()> {
+    change.validate()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let state = &mut ctx.accounts.state;
+    let pending = &mut ctx.accounts.pending_change;
+    pending.id = state.change_count;
+    pending.change = change.clone();
+    pending.rent_payer = ctx.accounts.payer.key();
+    pending.queued_at = now;
+    pending.execute_after = now.checked_add(state.timelock_delay).unwrap();
+    pending.expires_at = pending.execute_after.checked_add(CHANGE_EXECUTION_WINDOW).unwrap();
+    pending.bump = ctx.bumps.pending_change;
+
+    state.change_count = state.change_count.checked_add(1).unwrap();
+
+    emit_cpi!(ChangeQueued {
+        id: pending.id,
+        change,
+        actor: ctx.accounts.authority.key(),
+        execute_after: pending.execute_after,
+        expires_at: pending.expires_at,
+        timestamp: now,
+    });
+
+    msg!("Queued admin change {} (executable after {})", pending.id, pending.execute_after);
+    Ok(())
+}
+
+#[account]
+pub struct PendingChange {
+    pub id: u64,                      // 8
+    pub change: AdminChange,          // 1 + 32
+    pub rent_payer: Pubkey,           // 32
+    pub queued_at: i64,               // 8
+    pub execute_after: i64,           // 8
+    /// Last moment the change can execute; afterwards it can only be cancelled
+    pub expires_at: i64,              // 8
+    pub bump: u8,                     // 1
+}
+
+impl PendingChange {
+    pub const LEN: usize = 8 + 33 + 32 + 8 + 8 + 8 + 1;
+
+    pub fn check_executable(&self, now: i64) -> Result<()> {
+        require!(now >= self.execute_after, ErrorCode::TimelockNotElapsed);
+        require!(now <= self.expires_at, ErrorCode::ChangeExpired);
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AdminChange {
+    ReflectVault { new_vault: Pubkey },
+    Authority { new_authority: Pubkey },
+    Guardian { new_guardian: Pubkey },
+    RedemptionFee { fee_bps: u16 },
+    SupplyCap { cap: u64 },
+    TimelockDelay { delay: i64 },
+}
+
+impl AdminChange {
+    /// The same setting as `self`, holding its current value in `state`
+    pub fn current(&self, state: &TGEMPlusState) -> AdminChange {
+        match self {
+            AdminChange::ReflectVault { .. } => AdminChange::ReflectVault {
+                new_vault: state.reflect_vault,
+            },
+            AdminChange::Authority { .. } => AdminChange::Authority {
+                new_authority: state.authority,
+            },
+            AdminChange::Guardian { .. } => AdminChange::Guardian {
+                new_guardian: state.guardian,
+            },
+            AdminChange::RedemptionFee { .. } => AdminChange::RedemptionFee {
+                fee_bps: state.redemption_fee_bps,
+            },
+            AdminChange::SupplyCap { .. } => AdminChange::SupplyCap {
+                cap: state.supply_cap,
+            },
+            AdminChange::TimelockDelay { .. } => AdminChange::TimelockDelay {
+                delay: state.timelock_delay,
+            },
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            AdminChange::RedemptionFee { fee_bps } => {
+                require!(*fee_bps <= MAX_REDEMPTION_FEE_BPS, ErrorCode::FeeTooHigh)
+            }
+            AdminChange::TimelockDelay { delay } => require!(
+                (0..=MAX_TIMELOCK_DELAY).contains(delay),
+                ErrorCode::InvalidTimelockDelay
+            ),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Freeze minting and catalog redemption, snapshotting supply and backing assets.
+/// Returns the event for the caller to emit with `emit_cpi!`
+fn enter_shutdown(
+    state: &mut TGEMPlusState,
+    reason: ShutdownReason,
+    backing: u64,
+    actor: Option<Pubkey>,
+) -> Result<ShutdownTriggered> {
+    let now = Clock::get()?.unix_timestamp;
+    state.shutdown = true;
+    state.shutdown_at = now;
+    state.shutdown_supply = state.total_supply;
+    state.shutdown_assets = backing;
+
+    msg!(
+        "TGEM+ shut down: {} supply backed by {} assets",
+        state.shutdown_supply,
+        backing
+    );
+    Ok(ShutdownTriggered {
+        reason,
+        supply: state.shutdown_supply,
+        assets: backing,
+        actor,
+        timestamp: now,
+    })
+}
+
+/// What a project put into TGEM+. TGEM+ is fungible once minted, so a burn
+/// can't be traced back to the project that issued it: redemptions are
+/// counted on `TGEMPlusState` and never depend on the redeemer's registration
+#[account]
+pub struct ProjectLedger {
+    pub project: Pubkey,              // 32
+    pub funded_collateral: u64,       // 8
+    /// Unclaimed migration collateral returned to the authority
+    pub released_collateral: u64,     // 8
+    /// Minted or migrated for this project's users
+    pub minted_supply: u64,           // 8
+    pub bump: u8,                     // 1
+}
+
+impl ProjectLedger {
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct MigrationDistributor {
+    pub project: Pubkey,              // 32
+    pub merkle_root: [u8; 32],        // 32
+    pub num_leaves: u32,              // 4
+    pub collateral_funded: u64,       // 8
+    pub total_claimed: u64,           // 8
+    pub claimed_count: u32,           // 4
+    pub deadline: i64,                // 8
+    pub reclaimed: bool,              // 1
+    pub bump: u8,                     // 1
+    pub claimed_bitmap: Vec<u8>,      // 4 + ceil(num_leaves / 8)
+}
+
+impl MigrationDistributor {
+    pub const LEN: usize = 32 + 32 + 4 + 8 + 8 + 4 + 8 + 1 + 1 + 4;
+    /// Keeps the account within the 10KiB CPI allocation limit
+    pub const MAX_LEAVES: u32 = 64_000;
+
+    pub fn bitmap_len(num_leaves: u32) -> usize {
+        (num_leaves as usize).div_ceil(8)
+    }
+
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.claimed_bitmap[(index / 8) as usize] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u32) {
+        self.claimed_bitmap[(index / 8) as usize] |= 1 << (index % 8);
+    }
+}
+
+/// Leaf committed to by a migration Merkle tree: keccak(index_le || wallet || amount_le)
+fn migration_leaf(index: u32, wallet: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[&index.to_le_bytes(), wallet.as_ref(), &amount.to_le_bytes()]).0
+}
+
+/// Verify a keccak Merkle proof using sorted-pair hashing
+fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).0
+        } else {
+            keccak::hashv(&[sibling, &node]).0
+        }
+    });
+    computed == root
+}
+
+#[event]
+pub struct CouncilUpdated {
+    pub council: Pubkey,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouncilProposalCreated {
+    pub council: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouncilProposalApproved {
+    pub council: Pubkey,
+    pub proposal: Pubkey,
+    pub member: Pubkey,
+    pub approvals: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouncilProposalExecuted {
+    pub council: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouncilProposalCancelled {
+    pub council: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintEvent {
+    pub user: Pubkey,
+    pub project: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RedemptionEvent {
+    pub user: Pubkey,
+    pub redemption_type: RedemptionType,
+    pub item_id: Option<String>,
+    pub amount: u64,
+    pub collateral_released: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ShutdownTriggered {
+    pub reason: ShutdownReason,
+    pub supply: u64,
+    pub assets: u64,
+    /// None for a permissionless `check_reserves`
+    pub actor: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct YieldHarvested {
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MigrationClaimed {
+    pub user: Pubkey,
+    pub project: Pubkey,
+    pub index: u32,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MigrationCollateralReclaimed {
+    pub project: Pubkey,
+    pub amount: u64,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum RedemptionType {
+    Catalog,
+    Stablecoin,
+}
+
+#[event]
+pub struct VaultUpdated {
+    pub old_vault: Pubkey,
+    pub new_vault: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ChangeQueued {
+    pub id: u64,
+    pub change: AdminChange,
+    pub actor: Pubkey,
+    pub execute_after: i64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ChangeExecuted {
+    pub id: u64,
+    /// The changed setting's value before execution
+    pub previous: AdminChange,
+    pub change: AdminChange,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ChangeCancelled {
+    pub id: u64,
+    pub change: AdminChange,
+    pub canceller: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub version: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseChanged {
+    pub operation: PausableOperation,
+    pub old_paused: bool,
+    pub new_paused: bool,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuardianUpdated {
+    pub old_guardian: Pubkey,
+    pub new_guardian: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PausableOperation {
+    Mint,
+    CatalogRedemption,
+    StablecoinRedemption,
+    Yield,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum ShutdownReason {
+    Authority,
+    ReservesCheck,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Operation is paused")]
+    OperationPaused,
+    
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    
+    #[msg("Unauthorized")]
+    Unauthorized,
+    
+    #[msg("Item ID too long (max 64 chars)")]
+    ItemIdTooLong,
+    
+    #[msg("Insufficient collateral")]
+    InsufficientCollateral,
+    
+    #[msg("TGEM+ is not enabled for this project")]
+    TGEMPlusNotEnabled,
+    
+    #[msg("User is not registered with this project")]
+    UserNotRegistered,
+    
+    #[msg("Invalid number of migration leaves")]
+    InvalidLeafCount,
+    
+    #[msg("Leaf index out of range")]
+    InvalidLeafIndex,
+    
+    #[msg("Deadline must be in the future")]
+    InvalidDeadline,
+    
+    #[msg("Invalid Merkle proof")]
+    InvalidMerkleProof,
+    
+    #[msg("Migration allocation already claimed")]
+    AlreadyClaimed,
+    
+    #[msg("Migration claim window has closed")]
+    MigrationExpired,
+    
+    #[msg("Migration claim window is still open")]
+    MigrationStillActive,
+    
+    #[msg("Unclaimed migration collateral already reclaimed")]
+    AlreadyReclaimed,
+    
+    #[msg("Collateral vault does not match the configured vault")]
+    InvalidCollateralVault,
+    
+    #[msg("Protocol is shut down")]
+    ProtocolShutdown,
+    
+    #[msg("No pending authority transfer")]
+    NoPendingAuthority,
+    
+    #[msg("Mint would exceed the supply cap")]
+    SupplyCapExceeded,
+    
+    #[msg("Timelock delay has not elapsed")]
+    TimelockNotElapsed,
+    
+    #[msg("Invalid timelock delay")]
+    InvalidTimelockDelay,
+    
+    #[msg("Redemption fee too high")]
+    FeeTooHigh,
+    
+    #[msg("Account must be migrated to the current version first")]
+    AccountNotMigrated,
+    
+    #[msg("Account is already at the current version")]
+    AlreadyMigrated,
+    
+    #[msg("Account data does not match a known legacy layout")]
+    UnknownLayout,
+    
+    #[msg("Queued change expired before execution")]
+    ChangeExpired,
+    
+    #[msg("No vault assets above outstanding supply to harvest")]
+    NoYield,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_with_supply(total_supply: u64) -> TGEMPlusState {
+        TGEMPlusStateV0 {
+            authority: Pubkey::new_unique(),
+            reflect_vault: Pubkey::new_unique(),
+            total_supply,
+            total_collateral: total_supply,
+            paused: false,
+            bump: 255,
+        }
+        .into_current(Pubkey::new_unique())
+    }
+
+    #[test]
+    fn pro_rata_payout_shares_the_shortfall() {
+        // 1_000 supply backed by 600 after a 40% loss
+        let state = state_with_supply(1_000);
+        assert_eq!(state.pro_rata_payout(1_000, 600), 600);
+        assert_eq!(state.pro_rata_payout(250, 600), 150);
+        assert_eq!(state.pro_rata_payout(1, 600), 0);
+        assert_eq!(state_with_supply(0).pro_rata_payout(10, 600), 0);
+    }
+
+    #[test]
+    fn pro_rata_payout_is_order_independent() {
+        // Redeem in uneven chunks, updating supply and vault like redeem_stablecoin
+        let mut state = state_with_supply(1_000_000);
+        let mut vault = 333_333;
+        let mut paid = Vec::new();
+        for amount in [400_000, 1, 99_999, 500_000] {
+            let payout = state.pro_rata_payout(amount, vault);
+            state.total_supply -= amount;
+            vault -= payout;
+            paid.push((amount, payout));
+        }
+
+        assert_eq!(state.total_supply, 0);
+        for (amount, payout) in paid {
+            // Each redeemer gets the shutdown ratio, rounded down by at most one unit
+            let fair = amount as u128 * 333_333 / 1_000_000;
+            assert!(payout as u128 <= fair + 1 && payout as u128 + 1 >= fair);
+        }
+        // Rounding dust stays in the vault rather than overpaying anyone
+        assert!(vault < 4);
+    }
+
+    #[test]
+    fn backing_assets_exclude_migration_collateral() {
+        // 1_000 supply, 500 still owed to migration claimants, 200 lost
+        let mut state = state_with_supply(1_000);
+        state.migration_liabilities = 500;
+        assert_eq!(state.backing_assets(1_300), 800);
+        assert_eq!(state.pro_rata_payout(1_000, state.backing_assets(1_300)), 800);
+        // Claimants' collateral alone is never counted as backing
+        assert_eq!(state.backing_assets(400), 0);
+    }
+
+    #[test]
+    fn pause_flags_are_independent() {
+        let mut state = state_with_supply(0);
+        state.set_pause(PausableOperation::Yield, true);
+        assert!(state.is_paused(PausableOperation::Yield));
+        for operation in [
+            PausableOperation::Mint,
+            PausableOperation::CatalogRedemption,
+            PausableOperation::StablecoinRedemption,
+        ] {
+            assert!(!state.is_paused(operation));
+        }
+    }
+
+    fn pending(execute_after: i64) -> PendingChange {
+        PendingChange {
+            id: 0,
+            change: AdminChange::TimelockDelay { delay: 0 },
+            rent_payer: Pubkey::new_unique(),
+            queued_at: execute_after - DEFAULT_TIMELOCK_DELAY,
+            execute_after,
+            expires_at: execute_after + CHANGE_EXECUTION_WINDOW,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn pending_change_executes_only_inside_its_window() {
+        let change = pending(1_000_000);
+        assert!(change.check_executable(999_999).is_err());
+        assert!(change.check_executable(1_000_000).is_ok());
+        assert!(change.check_executable(1_000_000 + CHANGE_EXECUTION_WINDOW).is_ok());
+        assert!(change.check_executable(1_000_001 + CHANGE_EXECUTION_WINDOW).is_err());
+    }
+
+    #[test]
+    fn timelock_delay_bounds() {
+        assert!(AdminChange::TimelockDelay { delay: 0 }.validate().is_ok());
+        assert!(AdminChange::TimelockDelay { delay: MAX_TIMELOCK_DELAY }.validate().is_ok());
+        assert!(AdminChange::TimelockDelay { delay: MAX_TIMELOCK_DELAY + 1 }.validate().is_err());
+        assert!(AdminChange::TimelockDelay { delay: -1 }.validate().is_err());
+    }
+
+    #[test]
+    fn redemption_fee_bounds() {
+        assert!(AdminChange::RedemptionFee { fee_bps: MAX_REDEMPTION_FEE_BPS }.validate().is_ok());
+        assert!(AdminChange::RedemptionFee { fee_bps: MAX_REDEMPTION_FEE_BPS + 1 }.validate().is_err());
+
+        let mut state = state_with_supply(0);
+        state.redemption_fee_bps = 25;
+        assert_eq!(state.redemption_fee(10_000), 25);
+        assert_eq!(state.redemption_fee(39), 0);
+    }
+
+    #[test]
+    fn supply_cap_counts_outstanding_supply() {
+        let mut state = state_with_supply(900);
+        assert!(state.within_supply_cap(u64::MAX - 900));
+        state.supply_cap = 1_000;
+        assert!(state.within_supply_cap(100));
+        assert!(!state.within_supply_cap(101));
+    }
+
+    #[test]
+    fn executed_change_reports_previous_value() {
+        let state = state_with_supply(0);
+        let change = AdminChange::Guardian { new_guardian: Pubkey::new_unique() };
+        // Migrated state starts with the authority as guardian
+        assert_eq!(
+            change.current(&state),
+            AdminChange::Guardian { new_guardian: state.authority }
+        );
+        assert_eq!(
+            AdminChange::TimelockDelay { delay: 5 }.current(&state),
+            AdminChange::TimelockDelay { delay: DEFAULT_TIMELOCK_DELAY }
+        );
+    }
+
+    fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        if a <= b {
+            keccak::hashv(&[&a, &b]).0
+        } else {
+            keccak::hashv(&[&b, &a]).0
+        }
+    }
+
+    /// Four-leaf tree: returns the leaves and root
+    fn sample_tree(wallets: &[Pubkey; 4]) -> ([[u8; 32]; 4], [u8; 32]) {
+        let leaves = [
+            migration_leaf(0, &wallets[0], 100),
+            migration_leaf(1, &wallets[1], 250),
+            migration_leaf(2, &wallets[2], 75),
+            migration_leaf(3, &wallets[3], 1_000),
+        ];
+        let left = hash_pair(leaves[0], leaves[1]);
+        let right = hash_pair(leaves[2], leaves[3]);
+        (leaves, hash_pair(left, right))
+    }
+
+    #[test]
+    fn merkle_proof_accepts_every_leaf() {
+        let wallets = [(); 4].map(|_| Pubkey::new_unique());
+        let (leaves, root) = sample_tree(&wallets);
+        let left = hash_pair(leaves[0], leaves[1]);
+        let right = hash_pair(leaves[2], leaves[3]);
+
+        assert!(verify_merkle_proof(&[leaves[1], right], root, leaves[0]));
+        assert!(verify_merkle_proof(&[leaves[0], right], root, leaves[1]));
+        assert!(verify_merkle_proof(&[leaves[3], left], root, leaves[2]));
+        assert!(verify_merkle_proof(&[leaves[2], left], root, leaves[3]));
+    }
+
+    #[test]
+    fn merkle_proof_rejects_altered_claims() {
+        let wallets = [(); 4].map(|_| Pubkey::new_unique());
+        let (leaves, root) = sample_tree(&wallets);
+        let right = hash_pair(leaves[2], leaves[3]);
+        let proof = [leaves[1], right];
+
+        // Wrong amount, wrong index, or someone else's wallet
+        assert!(!verify_merkle_proof(&proof, root, migration_leaf(0, &wallets[0], 101)));
+        assert!(!verify_merkle_proof(&proof, root, migration_leaf(1, &wallets[0], 100)));
+        assert!(!verify_merkle_proof(&proof, root, migration_leaf(0, &Pubkey::new_unique(), 100)));
+        // Truncated proof
+        assert!(!verify_merkle_proof(&proof[..1], root, leaves[0]));
+    }
+
+    #[test]
+    fn merkle_single_leaf_tree_needs_empty_proof() {
+        let wallet = Pubkey::new_unique();
+        let leaf = migration_leaf(0, &wallet, 42);
+        assert!(verify_merkle_proof(&[], leaf, leaf));
+        assert!(!verify_merkle_proof(&[leaf], leaf, leaf));
+    }
+
+    #[test]
+    fn migration_leaf_layout() {
+        let wallet = Pubkey::new_unique();
+        let mut preimage = Vec::new();
+        preimage.extend_from_slice(&7u32.to_le_bytes());
+        preimage.extend_from_slice(wallet.as_ref());
+        preimage.extend_from_slice(&500u64.to_le_bytes());
+        assert_eq!(migration_leaf(7, &wallet, 500), keccak::hash(&preimage).0);
+    }
 }
@@ -1,4 +1,13 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use theras_common::council::{self, CouncilAccountMeta, CouncilError};
+use theras_common::migration::{read_legacy_account, realloc_account};
+
+/// Seconds per day, used for login cooldowns and daily budgets
+pub const SECONDS_PER_DAY: i64 = 86_400;
 
 declare_id!("BQm8t9GpXgyX1atwWGaTpYvjeyGZNcwTczJDauSM3f1v");
 
@@ -6,26 +15,165 @@ declare_id!("BQm8t9GpXgyX1atwWGaTpYvjeyGZNcwTczJDauSM3f1v");
 pub mod theras_protocol {
     use super::*;
 
+    /// Create the protocol-wide config (program upgrade authority only)
+    pub fn initialize_protocol_config(
+        ctx: Context<InitializeProtocolConfig>,
+        creation_policy: CreationPolicy,
+        treasury: Pubkey,
+        creation_fee: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.protocol_config;
+        config.admin = ctx.accounts.admin.key();
+        config.creation_policy = creation_policy;
+        config.treasury = treasury;
+        config.creation_fee = creation_fee;
+        config.project_count = 0;
+        config.bump = ctx.bumps.protocol_config;
+
+        msg!("Protocol config initialized, admin: {}", config.admin);
+        Ok(())
+    }
+
+    /// Update project creation policy, treasury and fee (protocol admin only)
+    pub fn update_protocol_config(
+        ctx: Context<UpdateProtocolConfig>,
+        creation_policy: CreationPolicy,
+        treasury: Pubkey,
+        creation_fee: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.protocol_config;
+        let old_creation_policy = config.creation_policy;
+        let old_treasury = config.treasury;
+        let old_creation_fee = config.creation_fee;
+        config.creation_policy = creation_policy;
+        config.treasury = treasury;
+        config.creation_fee = creation_fee;
+
+        emit_cpi!(ProtocolConfigUpdated {
+            old_creation_policy,
+            new_creation_policy: creation_policy,
+            old_treasury,
+            new_treasury: treasury,
+            old_creation_fee,
+            new_creation_fee: creation_fee,
+            actor: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Protocol config updated: {:?}", creation_policy);
+        Ok(())
+    }
+
+    /// Allow a wallet to create projects under the allowlist policy (protocol admin only)
+    pub fn allow_project_creator(
+        ctx: Context<AllowProjectCreator>,
+        creator: Pubkey,
+    ) -> Result<()> {
+        let allowance = &mut ctx.accounts.creator_allowance;
+        allowance.creator = creator;
+        allowance.bump = ctx.bumps.creator_allowance;
+
+        emit_cpi!(ProjectCreatorAllowed {
+            creator,
+            allowed: true,
+            actor: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Project creator allowed: {}", creator);
+        Ok(())
+    }
+
+    /// Remove a wallet from the project creator allowlist (protocol admin only)
+    pub fn revoke_project_creator(ctx: Context<RevokeProjectCreator>) -> Result<()> {
+        let creator = ctx.accounts.creator_allowance.creator;
+
+        emit_cpi!(ProjectCreatorAllowed {
+            creator,
+            allowed: false,
+            actor: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Project creator revoked: {}", creator);
+        Ok(())
+    }
+
     /// Initialize a new project with configuration
     pub fn initialize_project(
         ctx: Context<InitializeProject>,
         project_key: String,
         tgem_plus_enabled: bool,
     ) -> Result<()> {
-        require!(project_key.len() <= 32, ErrorCode::ProjectKeyTooLong);
+        validate_project_key(&project_key)?;
+
+        let config = &ctx.accounts.protocol_config;
+        match config.creation_policy {
+            CreationPolicy::Open => {}
+            CreationPolicy::Allowlist => {
+                require!(
+                    ctx.accounts.creator_allowance.is_some(),
+                    ErrorCode::CreatorNotAllowed
+                );
+            }
+            CreationPolicy::Fee => {
+                let treasury = ctx
+                    .accounts
+                    .treasury
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidTreasury)?;
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: treasury.to_account_info(),
+                    },
+                );
+                system_program::transfer(cpi_ctx, config.creation_fee)?;
+            }
+        }
         
+        let project_address = ctx.accounts.project.key();
         let project = &mut ctx.accounts.project;
         project.authority = ctx.accounts.authority.key();
+        project.pending_authority = None;
         project.project_key = project_key.clone();
         project.tgem_plus_enabled = tgem_plus_enabled;
         project.total_users = 0;
         project.total_events = 0;
         project.bump = ctx.bumps.project;
+        project.display_name = String::new();
+        project.uri = String::new();
+        project.category = ProjectCategory::Other;
+        project.logo_hash = [0; 32];
+        project.verified = false;
+        project.alias_count = 0;
+        project.closing = false;
+        project.restrict_user_close = false;
+        project.version = Project::VERSION;
+        project.activity_capacity = 0;
+        project.users_registered = 0;
+        project.tiers_enabled = false;
+        project.current_season = 0;
+        project.season_start = 0;
+        project.season_end = 0;
+        project.season_open = false;
+        project.closed_users = 0;
+        project.reserved = [0; Project::RESERVED_LEN];
+
+        // Append to the registry so clients can enumerate projects page by page
+        let config = &mut ctx.accounts.protocol_config;
+        project.registry_index = config.project_count;
+        let page = &mut ctx.accounts.registry_page;
+        page.page = ProjectRegistryPage::page_for(config.project_count);
+        page.projects.push(project_address);
+        page.bump = ctx.bumps.registry_page;
+        config.project_count = config.project_count.checked_add(1).unwrap();
         
         emit!(ProjectCreated {
-            project: ctx.accounts.project.key(),
+            project: project_address,
             authority: project.authority,
-            project_key,
+            project_key: project_key.clone(),
             timestamp: Clock::get()?.unix_timestamp,
         });
         
@@ -33,25 +181,277 @@ pub mod theras_protocol {
         Ok(())
     }
 
+    /// Update display metadata shown by clients (project authority only)
+    pub fn update_project_metadata(
+        ctx: Context<UpdateProject>,
+        display_name: String,
+        uri: String,
+        category: ProjectCategory,
+        logo_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            display_name.len() <= Project::MAX_DISPLAY_NAME_LEN,
+            ErrorCode::DisplayNameTooLong
+        );
+        require!(uri.len() <= Project::MAX_URI_LEN, ErrorCode::UriTooLong);
+
+        let project = &mut ctx.accounts.project;
+        project.display_name = display_name;
+        project.uri = uri;
+        project.category = category;
+        project.logo_hash = logo_hash;
+
+        emit_cpi!(ProjectMetadataUpdated {
+            project: project.key(),
+            display_name: project.display_name.clone(),
+            uri: project.uri.clone(),
+            category,
+            logo_hash,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Project metadata updated: {}", project.display_name);
+        Ok(())
+    }
+
+    /// Set or clear a project's verification badge (protocol admin only)
+    pub fn verify_project(
+        ctx: Context<ProtocolAdminProject>,
+        verified: bool,
+    ) -> Result<()> {
+        let project = &mut ctx.accounts.project;
+        let old_verified = project.verified;
+        project.verified = verified;
+
+        emit_cpi!(ProjectVerified {
+            project: project.key(),
+            old_verified,
+            new_verified: verified,
+            actor: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Project {} verified: {}", project.project_key, verified);
+        Ok(())
+    }
+
+    /// Register an additional key resolving to this project (project authority only)
+    pub fn add_project_alias(
+        ctx: Context<AddProjectAlias>,
+        alias: String,
+    ) -> Result<()> {
+        validate_project_key(&alias)?;
+
+        let project = &mut ctx.accounts.project;
+        require!(
+            project.alias_count < Project::MAX_ALIASES,
+            ErrorCode::TooManyAliases
+        );
+        project.alias_count += 1;
+
+        let project_alias = &mut ctx.accounts.project_alias;
+        project_alias.project = project.key();
+        project_alias.alias = alias.clone();
+        project_alias.bump = ctx.bumps.project_alias;
+
+        emit_cpi!(ProjectAliasUpdated {
+            project: project.key(),
+            alias: alias.clone(),
+            added: true,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Project alias added: {} -> {}", alias, project.project_key);
+        Ok(())
+    }
+
+    /// Remove an alias from this project (project authority only)
+    pub fn remove_project_alias(ctx: Context<RemoveProjectAlias>) -> Result<()> {
+        let project = &mut ctx.accounts.project;
+        project.alias_count = project.alias_count.saturating_sub(1);
+        let alias = ctx.accounts.project_alias.alias.clone();
+
+        emit_cpi!(ProjectAliasUpdated {
+            project: project.key(),
+            alias: alias.clone(),
+            added: false,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Project alias removed: {}", alias);
+        Ok(())
+    }
+
+    /// Hand a squatted project key to its rightful owner (protocol admin only)
+    pub fn reassign_project(
+        ctx: Context<ProtocolAdminProject>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let project = &mut ctx.accounts.project;
+        let old_authority = project.authority;
+        project.authority = new_authority;
+        project.pending_authority = None;
+
+        emit_cpi!(ProjectReassigned {
+            project: project.key(),
+            old_authority,
+            new_authority,
+            actor: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Project {} reassigned to: {}", project.project_key, new_authority);
+        Ok(())
+    }
+
+    /// Close a squatted project with no users so its key can be re-created
+    /// (protocol admin only, rent goes to the treasury). Its aliases and
+    /// tombstones must be reclaimed first
+    pub fn reclaim_project(ctx: Context<ReclaimProject>) -> Result<()> {
+        let project = &ctx.accounts.project;
+        require!(project.total_users == 0, ErrorCode::ProjectHasUsers);
+        require!(project.alias_count == 0, ErrorCode::ProjectHasAliases);
+        require!(project.closed_users == 0, ErrorCode::ProjectHasClosedUsers);
+        unregister_project(project, ctx.accounts.registry_page.as_deref_mut())?;
+
+        emit_cpi!(ProjectReclaimed {
+            project: project.key(),
+            project_key: project.project_key.clone(),
+            old_authority: project.authority,
+            actor: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Project reclaimed: {}", project.project_key);
+        Ok(())
+    }
+
+    /// Remove an alias of a squatted project ahead of `reclaim_project`
+    /// (protocol admin only, rent goes to the treasury)
+    pub fn reclaim_project_alias(ctx: Context<ReclaimProjectAlias>) -> Result<()> {
+        let project = &mut ctx.accounts.project;
+        project.alias_count = project.alias_count.saturating_sub(1);
+        let alias = ctx.accounts.project_alias.alias.clone();
+
+        emit_cpi!(ProjectAliasUpdated {
+            project: project.key(),
+            alias: alias.clone(),
+            added: false,
+            actor: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Project alias reclaimed: {}", alias);
+        Ok(())
+    }
+
+    /// Close a `ClosedUser` tombstone of a squatted project ahead of
+    /// `reclaim_project`, refunding whoever paid for it (protocol admin only)
+    pub fn reclaim_closed_user(ctx: Context<ReclaimClosedUser>, wallet: Pubkey) -> Result<()> {
+        let project = &mut ctx.accounts.project;
+        project.closed_users = project.closed_users.saturating_sub(1);
+
+        msg!("Reclaimed tombstone of {}", wallet);
+        Ok(())
+    }
+
+    /// Start retiring a project: blocks new registrations and events so
+    /// `User` accounts can be closed (project authority only, irreversible)
+    pub fn begin_project_sunset(ctx: Context<UpdateProject>) -> Result<()> {
+        let project = &mut ctx.accounts.project;
+        require!(!project.closing, ErrorCode::ProjectClosing);
+        project.closing = true;
+
+        emit_cpi!(ProjectSunsetStarted {
+            project: project.key(),
+            total_users: project.total_users,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Project sunset started: {}", project.project_key);
+        Ok(())
+    }
+
+    /// Close a `User` of a sunsetting project and any side accounts passed
+    /// with it, refunding rent to whoever paid for the user (permissionless crank)
+    pub fn close_sunset_user(ctx: Context<CloseSunsetUser>) -> Result<()> {
+        let project = &mut ctx.accounts.project;
+        project.total_users = project.total_users.saturating_sub(1);
+
+        emit!(UserAccountClosed {
+            user: ctx.accounts.user.wallet,
+            project: project.key(),
+            rent_payer: ctx.accounts.rent_payer.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Closed user {} of sunset project", ctx.accounts.user.wallet);
+        Ok(())
+    }
+
+    /// Close the `ClosedUser` tombstone of a wallet that left a sunsetting
+    /// project, refunding whoever paid for it (permissionless crank)
+    pub fn close_sunset_tombstone(ctx: Context<CloseSunsetTombstone>, wallet: Pubkey) -> Result<()> {
+        let project = &mut ctx.accounts.project;
+        project.closed_users = project.closed_users.saturating_sub(1);
+
+        msg!("Closed tombstone of {} for sunset project", wallet);
+        Ok(())
+    }
+
+    /// Close a sunset project once all users, tombstones and aliases are gone,
+    /// removing it from the registry (project authority only)
+    pub fn close_project(ctx: Context<CloseProject>) -> Result<()> {
+        let project = &ctx.accounts.project;
+        require!(project.total_users == 0, ErrorCode::ProjectHasUsers);
+        require!(project.alias_count == 0, ErrorCode::ProjectHasAliases);
+        require!(project.closed_users == 0, ErrorCode::ProjectHasClosedUsers);
+        unregister_project(project, ctx.accounts.registry_page.as_deref_mut())?;
+
+        emit_cpi!(ProjectClosed {
+            project: project.key(),
+            project_key: project.project_key.clone(),
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Project closed: {}", project.project_key);
+        Ok(())
+    }
+
     /// Register a new user for a project
     pub fn register_user(ctx: Context<RegisterUser>) -> Result<()> {
-        let user = &mut ctx.accounts.user;
-        user.project = ctx.accounts.project.key();
-        user.wallet = ctx.accounts.user_wallet.key();
-        user.daily_logins = 0;
-        user.quests = 0;
-        user.referrals = 0;
-        user.total_tgem_earned = 0;
-        user.last_login = 0;
-        user.bump = ctx.bumps.user;
+        let closed = read_closed_user(&ctx.accounts.closed_user)?;
         
         // Update project stats
         let project = &mut ctx.accounts.project;
-        project.total_users = project.total_users.saturating_add(1);
+        let user_index = project.admit_user(closed.as_ref());
+        
+        ctx.accounts.user.set_inner(User::new(
+            ctx.accounts.project.key(),
+            ctx.accounts.user_wallet.key(),
+            ctx.accounts.user_wallet.key(),
+            user_index,
+            ctx.bumps.user,
+        ));
+        if let Some(closed) = &closed {
+            ctx.accounts.user.resume(closed);
+        }
+        
+        let daily_stats = &mut ctx.accounts.daily_stats;
+        daily_stats.open(ctx.accounts.project.key(), ctx.bumps.daily_stats)?;
+        if closed.is_none() {
+            daily_stats.new_users = daily_stats.new_users.saturating_add(1);
+        }
         
         emit!(UserRegistered {
             user: ctx.accounts.user_wallet.key(),
             project: ctx.accounts.project.key(),
+            sponsored: false,
             timestamp: Clock::get()?.unix_timestamp,
         });
         
@@ -59,263 +459,4326 @@ pub mod theras_protocol {
         Ok(())
     }
 
-    /// Record daily login event
-    pub fn record_daily_login(ctx: Context<RecordEvent>) -> Result<()> {
-        require!(
-            ctx.accounts.user.project == ctx.accounts.project.key(),
-            ErrorCode::InvalidProject
-        );
-        require!(
-            ctx.accounts.user.wallet == ctx.accounts.user_wallet.key(),
-            ErrorCode::InvalidUser
-        );
-        
-        let current_time = Clock::get()?.unix_timestamp;
-        let user = &mut ctx.accounts.user;
-        
-        // Check if already logged in today (86400 seconds = 1 day)
-        require!(
-            current_time - user.last_login >= 86400,
-            ErrorCode::AlreadyLoggedInToday
-        );
-        
-        user.daily_logins = user.daily_logins.saturating_add(1);
-        user.last_login = current_time;
-        
-        // Update project stats
-        let project = &mut ctx.accounts.project;
-        project.total_events = project.total_events.saturating_add(1);
-        
-        emit!(EventRecorded {
-            user: ctx.accounts.user_wallet.key(),
-            project: ctx.accounts.project.key(),
-            event_type: EventType::DailyLogin,
-            count: user.daily_logins,
-            timestamp: current_time,
+    /// Create a project-funded sponsor that pays rent for new users
+    /// (project authority only)
+    pub fn create_rent_sponsor(
+        ctx: Context<CreateRentSponsor>,
+        daily_budget: u64,
+    ) -> Result<()> {
+        let sponsor = &mut ctx.accounts.rent_sponsor;
+        sponsor.project = ctx.accounts.project.key();
+        sponsor.daily_budget = daily_budget;
+        sponsor.day_index = 0;
+        sponsor.spent_today = 0;
+        sponsor.sponsored_count = 0;
+        sponsor.bump = ctx.bumps.rent_sponsor;
+
+        msg!("Rent sponsor created with daily budget: {}", daily_budget);
+        Ok(())
+    }
+
+    /// Change the sponsor's daily lamport budget (project authority only)
+    pub fn set_sponsor_budget(
+        ctx: Context<UpdateRentSponsor>,
+        daily_budget: u64,
+    ) -> Result<()> {
+        let sponsor = &mut ctx.accounts.rent_sponsor;
+        let old_daily_budget = sponsor.daily_budget;
+        sponsor.daily_budget = daily_budget;
+
+        emit_cpi!(SponsorBudgetUpdated {
+            project: sponsor.project,
+            old_daily_budget,
+            new_daily_budget: daily_budget,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
         });
-        
+
+        msg!("Rent sponsor daily budget: {}", daily_budget);
         Ok(())
     }
 
-    /// Record quest completion
-    pub fn record_quest(
-        ctx: Context<RecordEvent>,
-        quest_id: String,
+    /// Top up the sponsor's lamports (project authority only)
+    pub fn fund_rent_sponsor(
+        ctx: Context<FundRentSponsor>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: ctx.accounts.rent_sponsor.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, amount)?;
+
+        msg!("Rent sponsor funded: +{}", amount);
+        Ok(())
+    }
+
+    /// Take unspent lamports back out of the sponsor, leaving it rent-exempt
+    /// (project authority only)
+    pub fn withdraw_rent_sponsor(
+        ctx: Context<WithdrawRentSponsor>,
+        amount: u64,
     ) -> Result<()> {
+        let sponsor_info = ctx.accounts.rent_sponsor.to_account_info();
         require!(
-            ctx.accounts.user.project == ctx.accounts.project.key(),
-            ErrorCode::InvalidProject
+            sponsor_info.lamports().saturating_sub(amount)
+                >= Rent::get()?.minimum_balance(sponsor_info.data_len()),
+            ErrorCode::SponsorUnderfunded
         );
+        ctx.accounts.rent_sponsor.sub_lamports(amount)?;
+        ctx.accounts.authority.add_lamports(amount)?;
+
+        msg!("Rent sponsor withdrawn: -{}", amount);
+        Ok(())
+    }
+
+    /// Close the sponsor, returning all its lamports (project authority only).
+    /// Rent of users it sponsored still returns to its address on close
+    pub fn close_rent_sponsor(_ctx: Context<CloseRentSponsor>) -> Result<()> {
+        msg!("Rent sponsor closed");
+        Ok(())
+    }
+
+    /// Register a user whose `User` rent is paid by the project's sponsor;
+    /// the wallet only signs and the rent returns to the sponsor on close.
+    /// Each wallet is sponsored once, so a register/close loop can't burn the
+    /// daily budget; returning wallets use `register_user`
+    pub fn register_user_sponsored(ctx: Context<RegisterUserSponsored>) -> Result<()> {
+        let rent = Rent::get()?;
+        let user_info = ctx.accounts.user.to_account_info();
+        let stats_info = ctx.accounts.daily_stats.to_account_info();
+        let sponsor_info = ctx.accounts.rent_sponsor.to_account_info();
+        // The sponsor also opens today's stats account if this is the day's first activity
+        let create_stats = stats_info.data_is_empty();
+        let mut cost = rent
+            .minimum_balance(8 + User::LEN)
+            .saturating_sub(user_info.lamports());
+        if create_stats {
+            cost += rent
+                .minimum_balance(8 + DailyStats::LEN)
+                .saturating_sub(stats_info.lamports());
+        }
+
+        let sponsor = &mut ctx.accounts.rent_sponsor;
+        let today = DailyStats::today()?;
+        if sponsor.day_index != today {
+            sponsor.day_index = today;
+            sponsor.spent_today = 0;
+        }
+        let spent_today = sponsor
+            .spent_today
+            .checked_add(cost)
+            .ok_or(ErrorCode::SponsorBudgetExceeded)?;
+        require!(spent_today <= sponsor.daily_budget, ErrorCode::SponsorBudgetExceeded);
         require!(
-            ctx.accounts.user.wallet == ctx.accounts.user_wallet.key(),
-            ErrorCode::InvalidUser
+            sponsor_info.lamports().saturating_sub(cost)
+                >= rent.minimum_balance(sponsor_info.data_len()),
+            ErrorCode::SponsorUnderfunded
         );
-        require!(quest_id.len() <= 64, ErrorCode::QuestIdTooLong);
-        
-        let user = &mut ctx.accounts.user;
-        user.quests = user.quests.saturating_add(1);
-        
-        // Update project stats
-        let project = &mut ctx.accounts.project;
-        project.total_events = project.total_events.saturating_add(1);
-        
-        emit!(EventRecorded {
-            user: ctx.accounts.user_wallet.key(),
-            project: ctx.accounts.project.key(),
-            event_type: EventType::Quest,
-            count: user.quests,
+        sponsor.spent_today = spent_today;
+        sponsor.sponsored_count = sponsor.sponsored_count.saturating_add(1);
+
+        let project_key = ctx.accounts.project.key();
+        let wallet_key = ctx.accounts.user_wallet.key();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        create_funded_pda(
+            &sponsor_info,
+            &user_info,
+            &system_program,
+            8 + User::LEN,
+            &[b"user", project_key.as_ref(), wallet_key.as_ref(), &[ctx.bumps.user]],
+        )?;
+
+        let user_index = ctx.accounts.project.admit_user(None);
+
+        let user = User::new(
+            project_key,
+            wallet_key,
+            sponsor_info.key(),
+            user_index,
+            ctx.bumps.user,
+        );
+        user.try_serialize(&mut &mut user_info.try_borrow_mut_data()?[..])?;
+
+        let mut daily_stats = if create_stats {
+            create_funded_pda(
+                &sponsor_info,
+                &stats_info,
+                &system_program,
+                8 + DailyStats::LEN,
+                &[
+                    b"daily_stats",
+                    project_key.as_ref(),
+                    today.to_le_bytes().as_ref(),
+                    &[ctx.bumps.daily_stats],
+                ],
+            )?;
+            DailyStats {
+                project: project_key,
+                day: today,
+                active_users: 0,
+                new_users: 0,
+                daily_logins: 0,
+                quests: 0,
+                referrals: 0,
+                custom_events: 0,
+                bump: ctx.bumps.daily_stats,
+            }
+        } else {
+            DailyStats::try_deserialize(&mut &stats_info.try_borrow_data()?[..])?
+        };
+        daily_stats.new_users = daily_stats.new_users.saturating_add(1);
+        daily_stats.try_serialize(&mut &mut stats_info.try_borrow_mut_data()?[..])?;
+
+        emit!(UserRegistered {
+            user: wallet_key,
+            project: project_key,
+            sponsored: true,
             timestamp: Clock::get()?.unix_timestamp,
         });
-        
+
+        msg!("Sponsored user registered: {}", wallet_key);
         Ok(())
     }
 
-    /// Record referral
-    pub fn record_referral(
-        ctx: Context<RecordEvent>,
-        referred_user: Pubkey,
-    ) -> Result<()> {
-        require!(
-            ctx.accounts.user.project == ctx.accounts.project.key(),
-            ErrorCode::InvalidProject
-        );
+    /// Deregister and close the signer's `User`, refunding rent to its payer.
+    /// `remaining_accounts` holds the writable `Leaderboard`s to remove the
+    /// wallet from; any left over can be cleaned with `evict_leaderboard_entry`
+    pub fn close_user<'info>(ctx: Context<'_, '_, 'info, 'info, CloseUser<'info>>) -> Result<()> {
+        let user = &ctx.accounts.user;
+        if ctx.accounts.project.restrict_user_close {
+            require!(user.pending_rewards == 0, ErrorCode::UnclaimedRewards);
+            require!(
+                user.referral_bindings == 0 && ctx.accounts.referral_binding.data_is_empty(),
+                ErrorCode::ActiveReferrals
+            );
+        }
+
+        // A buffer left behind would be silently skipped by a re-registered user
         require!(
-            ctx.accounts.user.wallet == ctx.accounts.user_wallet.key(),
-            ErrorCode::InvalidUser
+            !user.has_activity || ctx.accounts.activity.is_some(),
+            ErrorCode::ActivityAccountRequired
         );
-        
-        let user = &mut ctx.accounts.user;
-        user.referrals = user.referrals.saturating_add(1);
-        
-        // Update project stats
+
+        // The tombstone's rent comes out of the user's, so whoever paid for the
+        // user pays for it and the leaving wallet is never charged
+        let closed_info = ctx.accounts.closed_user.to_account_info();
+        let rent_payer = match read_closed_user(&closed_info)? {
+            Some(closed) => closed.rent_payer,
+            None => {
+                let project_key = ctx.accounts.project.key();
+                let wallet_key = ctx.accounts.user_wallet.key();
+                create_funded_pda(
+                    &user.to_account_info(),
+                    &closed_info,
+                    &ctx.accounts.system_program.to_account_info(),
+                    8 + ClosedUser::LEN,
+                    &[b"closed_user", project_key.as_ref(), wallet_key.as_ref(), &[ctx.bumps.closed_user]],
+                )?;
+                ctx.accounts.project.closed_users = ctx.accounts.project.closed_users.saturating_add(1);
+                user.rent_payer
+            }
+        };
+        ClosedUser::from_user(user, rent_payer, ctx.bumps.closed_user)
+            .try_serialize(&mut &mut closed_info.try_borrow_mut_data()?[..])?;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        evict_from_leaderboards(
+            ctx.remaining_accounts,
+            &ctx.accounts.project,
+            ctx.accounts.user_wallet.key(),
+            current_time,
+        )?;
+
         let project = &mut ctx.accounts.project;
-        project.total_events = project.total_events.saturating_add(1);
-        
-        emit!(EventRecorded {
+        project.total_users = project.total_users.saturating_sub(1);
+
+        emit!(UserDeregistered {
             user: ctx.accounts.user_wallet.key(),
-            project: ctx.accounts.project.key(),
-            event_type: EventType::Referral,
-            count: user.referrals,
-            timestamp: Clock::get()?.unix_timestamp,
+            project: project.key(),
+            rent_payer: ctx.accounts.rent_payer.key(),
+            timestamp: current_time,
         });
-        
+
+        msg!("User deregistered: {}", ctx.accounts.user_wallet.key());
         Ok(())
     }
 
-    /// Update TGEM earned (called by backend after minting)
-    pub fn update_tgem_earned(
-        ctx: Context<UpdateTGEM>,
-        amount: u64,
+    /// Record daily login event. `remaining_accounts` holds any writable
+    /// `Leaderboard`s to update with the user's new scores
+    pub fn record_daily_login<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RecordEvent<'info>>,
     ) -> Result<()> {
-        let user = &mut ctx.accounts.user;
-        user.total_tgem_earned = user.total_tgem_earned.saturating_add(amount);
-        
-        msg!("Updated TGEM earned for user: {} (+{})", ctx.accounts.user_wallet.key(), amount);
-        Ok(())
+        let current_time = Clock::get()?.unix_timestamp;
+        let count = apply_event(&mut ctx.accounts.user, &EventType::DailyLogin, current_time)?;
+        record_hooks(
+            ctx.accounts.hooks(&ctx.bumps, ctx.remaining_accounts),
+            EventType::DailyLogin,
+            [0; 32],
+            count,
+            0,
+            current_time,
+        )
     }
 
-    /// Update project configuration
-    pub fn update_project_config(
-        ctx: Context<UpdateProject>,
-        tgem_plus_enabled: Option<bool>,
+    /// Record quest completion (leaderboards as in `record_daily_login`)
+    pub fn record_quest<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RecordEvent<'info>>,
+        quest_id: String,
     ) -> Result<()> {
-        let project = &mut ctx.accounts.project;
+        require!(quest_id.len() <= 64, ErrorCode::QuestIdTooLong);
         
-        if let Some(enabled) = tgem_plus_enabled {
-            project.tgem_plus_enabled = enabled;
-            msg!("TGEM+ enabled: {}", enabled);
+        let current_time = Clock::get()?.unix_timestamp;
+        let count = apply_event(&mut ctx.accounts.user, &EventType::Quest, current_time)?;
+        record_hooks(
+            ctx.accounts.hooks(&ctx.bumps, ctx.remaining_accounts),
+            EventType::Quest,
+            keccak::hash(quest_id.as_bytes()).to_bytes(),
+            count,
+            0,
+            current_time,
+        )
+    }
+
+    /// Record referral and bind `referred_user` to the referrer until the
+    /// binding is released (leaderboards as in `record_daily_login`)
+    pub fn record_referral<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RecordReferral<'info>>,
+        referred_user: Pubkey,
+    ) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        ctx.accounts.binding.set_inner(ReferralBinding {
+            project: ctx.accounts.event.project.key(),
+            referrer: ctx.accounts.event.user.key(),
+            referred: referred_user,
+            rent_payer: ctx.accounts.event.payer.key(),
+            created_at: current_time,
+            bump: ctx.bumps.binding,
+        });
+
+        let user = &mut ctx.accounts.event.user;
+        user.referral_bindings = user.referral_bindings.saturating_add(1);
+        let count = apply_event(user, &EventType::Referral, current_time)?;
+        record_hooks(
+            ctx.accounts.event.hooks(&ctx.bumps.event, ctx.remaining_accounts),
+            EventType::Referral,
+            referred_user.to_bytes(),
+            count,
+            0,
+            current_time,
+        )
+    }
+
+    /// End a referral binding, letting both sides close their accounts under
+    /// `restrict_user_close` (referrer's wallet or project authority)
+    pub fn release_referral(ctx: Context<ReleaseReferral>) -> Result<()> {
+        let binding = &ctx.accounts.binding;
+        let signer = ctx.accounts.signer.key();
+        let mut authorized = signer == ctx.accounts.project.authority;
+        // The referrer may have closed their account without the restriction
+        let referrer_info = &ctx.accounts.referrer;
+        if !referrer_info.data_is_empty() {
+            require_keys_eq!(*referrer_info.owner, crate::ID, ErrorCode::InvalidUser);
+            let mut referrer = User::try_deserialize(&mut &referrer_info.try_borrow_data()?[..])?;
+            authorized |= signer == referrer.wallet;
+            referrer.referral_bindings = referrer.referral_bindings.saturating_sub(1);
+            referrer.try_serialize(&mut &mut referrer_info.try_borrow_mut_data()?[..])?;
         }
-        
+        require!(authorized, ErrorCode::Unauthorized);
+
+        msg!("Referral released: {}", binding.referred);
         Ok(())
     }
+
+    /// Record many built-in events in one instruction (project authority only).
+    /// Unlike the single-user `record_*` instructions, users don't sign: the
+    /// project authority vouches for every entry, so batches should only carry
+    /// events the project's backend has verified itself. Entries a user isn't
+    /// eligible for (e.g. a second login today) are skipped and reported with
+    /// `BatchEventSkipped` instead of failing the batch. Batched referrals
+    /// count towards the referrer but create no `ReferralBinding`.
+    /// `remaining_accounts` starts with any writable `Leaderboard`s to update,
+    /// followed by the writable `User` accounts that
+    /// `BatchEvent::user_index` points into, each followed by its
+    /// `UserActivity` PDA when the project keeps activity history (left
+    /// unlogged if the user never created one) and its
+    /// `SeasonStats` PDA (created if needed) while a season is live
+    pub fn record_events_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RecordEventsBatch<'info>>,
+        events: Vec<BatchEvent>,
+    ) -> Result<()> {
+        require!(!events.is_empty(), ErrorCode::EmptyBatch);
+
+        let project_key = ctx.accounts.project.key();
+        let current_time = Clock::get()?.unix_timestamp;
+        let keeps_activity = ctx.accounts.project.activity_capacity > 0;
+        let live_season = ctx.accounts.project.live_season(current_time);
+        let stride = 1 + keeps_activity as usize + live_season.is_some() as usize;
+        let payer = ctx.accounts.payer.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let mut users = Vec::new();
+        let mut activities = Vec::new();
+        let mut season_stats = Vec::new();
+        let board_count = ctx
+            .remaining_accounts
+            .iter()
+            .take_while(|info| is_leaderboard(info))
+            .count();
+        let (boards, groups) = ctx.remaining_accounts.split_at(board_count);
+        for group in groups.chunks(stride) {
+            require!(group.len() == stride, ErrorCode::InvalidBatchAccounts);
+            let mut group = group.iter();
+            let user = load_batch_user(group.next().unwrap(), &project_key)?;
+            if keeps_activity {
+                activities.push(load_batch_activity(group.next().unwrap(), &user.key())?);
+            }
+            if let Some(season) = live_season {
+                season_stats.push(load_batch_season_stats(
+                    group.next().unwrap(),
+                    &project_key,
+                    season,
+                    &user.key(),
+                    &payer,
+                    &system_program,
+                )?);
+            }
+            users.push(user);
+        }
+        // A repeated account would be written back twice, losing one copy's updates
+        for (i, user) in users.iter().enumerate() {
+            require!(
+                users[..i].iter().all(|other| other.key() != user.key()),
+                ErrorCode::DuplicateBatchUser
+            );
+        }
+
+        let daily_stats = &mut ctx.accounts.daily_stats;
+        daily_stats.open(project_key, ctx.bumps.daily_stats)?;
+
+        let mut skipped = 0u64;
+        for (position, event) in events.iter().enumerate() {
+            let index = event.user_index as usize;
+            let user = users.get_mut(index).ok_or(ErrorCode::InvalidUserIndex)?;
+            let count = match apply_event(user, &event.event_type, current_time) {
+                Ok(count) => count,
+                Err(err) => {
+                    emit!(BatchEventSkipped {
+                        user: user.wallet,
+                        project: project_key,
+                        position: position as u32,
+                        event_type: event.event_type.clone(),
+                        error_code: match err {
+                            Error::AnchorError(err) => err.error_code_number,
+                            Error::ProgramError(_) => 0,
+                        },
+                        timestamp: current_time,
+                    });
+                    skipped += 1;
+                    continue;
+                }
+            };
+            daily_stats.record_event(user, &event.event_type);
+            let xp = award_xp(
+                &ctx.accounts.project,
+                ctx.accounts.tier_config.as_deref(),
+                user,
+                |config| config.event_xp(&event.event_type),
+            )?;
+            if let Some(stats) = season_stats.get_mut(index) {
+                stats.record_event(&event.event_type, xp);
+            }
+            if let Some(Some(activity)) = activities.get(index) {
+                append_activity(
+                    activity,
+                    ActivityEntry::new(&event.event_type, current_time, 0, event.payload_hash),
+                )?;
+            }
+
+            let (event_seq, event_chain_hash) =
+                user.chain_event(&event.event_type, &event.payload_hash, count, current_time);
+            emit!(EventRecorded {
+                user: user.wallet,
+                project: project_key,
+                event_type: event.event_type.clone(),
+                payload_hash: event.payload_hash,
+                count,
+                event_seq,
+                event_chain_hash,
+                timestamp: current_time,
+            });
+        }
+
+        for (index, user) in users.iter().enumerate() {
+            update_leaderboards(
+                boards,
+                &ctx.accounts.project,
+                user,
+                season_stats.get(index).map(|stats| &**stats),
+                current_time,
+            )?;
+            user.exit(ctx.program_id)?;
+        }
+        for stats in &season_stats {
+            stats.exit(ctx.program_id)?;
+        }
+
+        let recorded = events.len() as u64 - skipped;
+        let project = &mut ctx.accounts.project;
+        project.total_events = project.total_events.saturating_add(recorded);
+
+        msg!(
+            "Recorded {} events for {} users, skipped {}",
+            recorded,
+            users.len(),
+            skipped
+        );
+        Ok(())
+    }
+
+    /// Define a project-specific event type users can be rewarded for
+    /// (project authority only)
+    pub fn create_event_definition(
+        ctx: Context<CreateEventDefinition>,
+        event_id: u16,
+        name: String,
+        reward: u64,
+        cooldown: i64,
+        cap: u64,
+        required_signer: RequiredSigner,
+    ) -> Result<()> {
+        require!(name.len() <= EventDefinition::MAX_NAME_LEN, ErrorCode::EventNameTooLong);
+        require!(cooldown >= 0, ErrorCode::InvalidCooldown);
+
+        let definition = &mut ctx.accounts.event_definition;
+        definition.project = ctx.accounts.project.key();
+        definition.event_id = event_id;
+        definition.name = name;
+        definition.reward = reward;
+        definition.cooldown = cooldown;
+        definition.cap = cap;
+        definition.required_signer = required_signer;
+        definition.active = true;
+        definition.total_recorded = 0;
+        definition.bump = ctx.bumps.event_definition;
+
+        emit_cpi!(EventDefinitionUpdated {
+            project: definition.project,
+            event_id,
+            reward,
+            cooldown,
+            cap,
+            required_signer,
+            active: true,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Event definition {} created: {}", event_id, definition.name);
+        Ok(())
+    }
+
+    /// Change an event definition's reward rules, or retire it with `active = false`
+    /// (project authority only)
+    pub fn update_event_definition(
+        ctx: Context<UpdateEventDefinition>,
+        reward: u64,
+        cooldown: i64,
+        cap: u64,
+        required_signer: RequiredSigner,
+        active: bool,
+    ) -> Result<()> {
+        require!(cooldown >= 0, ErrorCode::InvalidCooldown);
+
+        let definition = &mut ctx.accounts.event_definition;
+        definition.reward = reward;
+        definition.cooldown = cooldown;
+        definition.cap = cap;
+        definition.required_signer = required_signer;
+        definition.active = active;
+
+        emit_cpi!(EventDefinitionUpdated {
+            project: definition.project,
+            event_id: definition.event_id,
+            reward,
+            cooldown,
+            cap,
+            required_signer,
+            active,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Event definition {} updated", definition.event_id);
+        Ok(())
+    }
+
+    /// Record an occurrence of a project-defined event, crediting its reward
+    /// to the user's `pending_rewards` (leaderboards as in `record_daily_login`)
+    pub fn record_custom_event<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RecordCustomEvent<'info>>,
+        event_id: u16,
+        payload_hash: [u8; 32],
+    ) -> Result<()> {
+        let definition = &mut ctx.accounts.event_definition;
+        let user_signed = ctx.accounts.user_wallet.is_signer;
+        let authority_signed = ctx.accounts.authority.is_some();
+        require!(
+            definition.required_signer.satisfied(user_signed, authority_signed),
+            ErrorCode::MissingRequiredSigner
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let counters = &mut ctx.accounts.event_counters;
+        if counters.user == Pubkey::default() {
+            counters.user = ctx.accounts.user.key();
+            counters.bump = ctx.bumps.event_counters;
+        }
+        let count = counters.entry(event_id)?.record(definition, current_time)?;
+
+        definition.total_recorded = definition.total_recorded.saturating_add(1);
+
+        // Scaled by the tier the user held when the event happened
+        let reward = match ctx.accounts.tier_config.as_deref() {
+            Some(config) => {
+                config.scale_reward(config.tier_for(ctx.accounts.user.xp), definition.reward)
+            }
+            None => definition.reward,
+        };
+        let user = &mut ctx.accounts.user;
+        user.pending_rewards = user.pending_rewards.saturating_add(reward);
+        record_hooks(
+            ctx.accounts.hooks(&ctx.bumps, ctx.remaining_accounts),
+            EventType::Custom(event_id),
+            payload_hash,
+            count,
+            reward,
+            current_time,
+        )
+    }
+
+    /// Close the stats account of a day that has ended, once it has been
+    /// indexed (project authority only)
+    pub fn close_daily_stats(ctx: Context<CloseDailyStats>) -> Result<()> {
+        msg!("Daily stats closed for day: {}", ctx.accounts.daily_stats.day);
+        Ok(())
+    }
+
+    /// Create a user's activity ring buffer, sized by the project's current
+    /// `activity_capacity` (permissionless, payer covers rent)
+    pub fn init_user_activity(ctx: Context<InitUserActivity>) -> Result<()> {
+        let mut activity = ctx.accounts.activity.load_init()?;
+        activity.user = ctx.accounts.user.key();
+        activity.rent_payer = ctx.accounts.payer.key();
+        activity.capacity = ctx.accounts.project.activity_capacity as u32;
+        activity.head = 0;
+        activity.len = 0;
+        activity.bump = ctx.bumps.activity;
+        ctx.accounts.user.has_activity = true;
+
+        msg!("User activity created with capacity: {}", activity.capacity);
+        Ok(())
+    }
+
+    /// Close a user's activity buffer, refunding whoever paid for it. The
+    /// user can create a fresh one afterwards, e.g. to pick up a new capacity
+    pub fn close_user_activity(ctx: Context<CloseUserActivity>) -> Result<()> {
+        ctx.accounts.user.has_activity = false;
+
+        msg!("User activity closed: {}", ctx.accounts.user_wallet.key());
+        Ok(())
+    }
+
+    /// Create or replace the project's XP weights and tier table, and start
+    /// requiring it in every `record_*` (project authority only). Users'
+    /// stored tiers catch up with a new table on their next XP change; reward
+    /// scaling always uses the tier their XP earns under the current table
+    pub fn set_tier_config(
+        ctx: Context<SetTierConfig>,
+        xp_weights: XpWeights,
+        boundaries: Vec<u64>,
+        multipliers_bps: Vec<u16>,
+    ) -> Result<()> {
+        require!(
+            boundaries.len() < TierConfig::MAX_TIERS
+                && multipliers_bps.len() == boundaries.len() + 1,
+            ErrorCode::InvalidTierConfig
+        );
+        require!(
+            boundaries.windows(2).all(|pair| pair[0] < pair[1])
+                && multipliers_bps
+                    .iter()
+                    .all(|bps| *bps <= TierConfig::MAX_MULTIPLIER_BPS),
+            ErrorCode::InvalidTierConfig
+        );
+
+        let tier_config = &mut ctx.accounts.tier_config;
+        tier_config.project = ctx.accounts.project.key();
+        tier_config.xp_weights = xp_weights.clone();
+        tier_config.boundaries = boundaries.clone();
+        tier_config.multipliers_bps = multipliers_bps.clone();
+        tier_config.bump = ctx.bumps.tier_config;
+
+        ctx.accounts.project.tiers_enabled = true;
+
+        emit_cpi!(TierConfigUpdated {
+            project: ctx.accounts.project.key(),
+            xp_weights,
+            boundaries,
+            multipliers_bps,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Tier config set with {} tiers", tier_config.boundaries.len() + 1);
+        Ok(())
+    }
+
+    /// Stop tracking XP and tiers and close the tier config (project
+    /// authority only). Stored XP and tiers stay as they were
+    pub fn disable_tiers(ctx: Context<DisableTiers>) -> Result<()> {
+        ctx.accounts.project.tiers_enabled = false;
+
+        emit_cpi!(TiersDisabled {
+            project: ctx.accounts.project.key(),
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Tiers disabled for project: {}", ctx.accounts.project.project_key);
+        Ok(())
+    }
+
+    /// Open the project's next season; `record_*` attributes events between
+    /// `start` and `end` to it (project authority only)
+    pub fn start_season(
+        ctx: Context<StartSeason>,
+        start: i64,
+        end: i64,
+    ) -> Result<()> {
+        require!(start < end, ErrorCode::InvalidSeasonWindow);
+
+        let project = &mut ctx.accounts.project;
+        project.current_season = project.current_season.checked_add(1).unwrap();
+        project.season_start = start;
+        project.season_end = end;
+        project.season_open = true;
+
+        let season = &mut ctx.accounts.season;
+        season.project = project.key();
+        season.number = project.current_season;
+        season.start = start;
+        season.end = end;
+        season.closed_at = 0;
+        season.bump = ctx.bumps.season;
+
+        emit_cpi!(SeasonStarted {
+            project: season.project,
+            number: season.number,
+            start,
+            end,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Season {} started", season.number);
+        Ok(())
+    }
+
+    /// Close the current season, freezing its `SeasonStats` for end-of-season
+    /// rewards (project authority only)
+    pub fn close_season(ctx: Context<CloseSeason>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        ctx.accounts.project.season_open = false;
+
+        let season = &mut ctx.accounts.season;
+        season.closed_at = current_time;
+
+        emit_cpi!(SeasonClosed {
+            project: season.project,
+            number: season.number,
+            actor: ctx.accounts.authority.key(),
+            timestamp: current_time,
+        });
+
+        msg!("Season {} closed", season.number);
+        Ok(())
+    }
+
+    /// Close a season's account once it has been closed and indexed
+    /// (project authority only)
+    pub fn close_season_account(ctx: Context<CloseSeasonAccount>) -> Result<()> {
+        msg!("Season {} account closed", ctx.accounts.season.number);
+        Ok(())
+    }
+
+    /// Close a user's `SeasonStats` for a season that is no longer live, once
+    /// its rewards are settled (project authority only)
+    pub fn close_season_stats(ctx: Context<CloseSeasonStats>) -> Result<()> {
+        msg!("Season {} stats closed", ctx.accounts.season_stats.season);
+        Ok(())
+    }
+
+    /// Close a leaderboard (project authority only)
+    pub fn close_leaderboard(ctx: Context<CloseLeaderboard>) -> Result<()> {
+        msg!("Leaderboard closed: {}", ctx.accounts.leaderboard.key());
+        Ok(())
+    }
+
+    /// Create a top-`capacity` leaderboard ranking the project's users by
+    /// `metric`, over their lifetime (`season` 0) or one season (project authority only)
+    pub fn create_leaderboard(
+        ctx: Context<CreateLeaderboard>,
+        metric: LeaderboardMetric,
+        season: u32,
+        capacity: u32,
+    ) -> Result<()> {
+        require!(
+            capacity > 0 && capacity <= Leaderboard::MAX_CAPACITY,
+            ErrorCode::InvalidLeaderboardCapacity
+        );
+        require!(
+            season <= ctx.accounts.project.current_season + 1,
+            ErrorCode::InvalidLeaderboard
+        );
+        require!(
+            season == 0 || metric != LeaderboardMetric::TgemEarned,
+            ErrorCode::UnsupportedLeaderboardMetric
+        );
+
+        let mut leaderboard = ctx.accounts.leaderboard.load_init()?;
+        leaderboard.project = ctx.accounts.project.key();
+        leaderboard.season = season;
+        leaderboard.capacity = capacity;
+        leaderboard.len = 0;
+        leaderboard.metric = metric as u8;
+        leaderboard.bump = ctx.bumps.leaderboard;
+
+        emit_cpi!(LeaderboardCreated {
+            project: leaderboard.project,
+            leaderboard: ctx.accounts.leaderboard.key(),
+            metric,
+            season,
+            capacity,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Leaderboard created with capacity: {}", capacity);
+        Ok(())
+    }
+
+    /// Submit a user's current score to a leaderboard, e.g. one created after
+    /// they last recorded an event (permissionless)
+    pub fn submit_leaderboard_score(ctx: Context<SubmitLeaderboardScore>) -> Result<()> {
+        let score = {
+            let leaderboard = ctx.accounts.leaderboard.load()?;
+            leaderboard_score(
+                &leaderboard,
+                &ctx.accounts.user,
+                ctx.accounts.season_stats.as_deref(),
+            )?
+        };
+        submit_score(&ctx.accounts.leaderboard, ctx.accounts.user.wallet, score)?;
+
+        msg!("Leaderboard score submitted: {}", score);
+        Ok(())
+    }
+
+    /// Drop a wallet without a `User` from a leaderboard, e.g. one its
+    /// `close_user` didn't pass (permissionless)
+    pub fn evict_leaderboard_entry(
+        ctx: Context<EvictLeaderboardEntry>,
+        wallet: Pubkey,
+    ) -> Result<()> {
+        evict_entry(&ctx.accounts.leaderboard, wallet)?;
+
+        msg!("Leaderboard entry evicted: {}", wallet);
+        Ok(())
+    }
+
+    /// Update TGEM earned (called by backend after minting). `remaining_accounts`
+    /// holds any writable lifetime `Leaderboard`s to update
+    pub fn update_tgem_earned<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateTGEM<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let user = &mut ctx.accounts.user;
+        let old_total = user.total_tgem_earned;
+        user.total_tgem_earned = user.total_tgem_earned.saturating_add(amount);
+        // Minted TGEM settles any rewards credited on-chain beforehand
+        user.pending_rewards = user.pending_rewards.saturating_sub(amount);
+        award_xp(
+            &ctx.accounts.project,
+            ctx.accounts.tier_config.as_deref(),
+            user,
+            |config| config.tgem_xp(amount),
+        )?;
+        let current_time = Clock::get()?.unix_timestamp;
+        update_leaderboards(
+            ctx.remaining_accounts,
+            &ctx.accounts.project,
+            user,
+            None,
+            current_time,
+        )?;
+
+        emit_cpi!(TgemEarnedUpdated {
+            project: ctx.accounts.project.key(),
+            user: ctx.accounts.user_wallet.key(),
+            amount,
+            old_total,
+            new_total: user.total_tgem_earned,
+            actor: ctx.accounts.authority.key(),
+            timestamp: current_time,
+        });
+        
+        msg!("Updated TGEM earned for user: {} (+{})", ctx.accounts.user_wallet.key(), amount);
+        Ok(())
+    }
+
+    /// Credit rewards owed to a user but not yet minted (called by backend)
+    pub fn credit_rewards(
+        ctx: Context<UpdateTGEM>,
+        amount: u64,
+    ) -> Result<()> {
+        let user = &mut ctx.accounts.user;
+        let old_pending = user.pending_rewards;
+        user.pending_rewards = user.pending_rewards.saturating_add(amount);
+
+        emit_cpi!(RewardsCredited {
+            project: ctx.accounts.project.key(),
+            user: ctx.accounts.user_wallet.key(),
+            amount,
+            old_pending,
+            new_pending: user.pending_rewards,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Credited rewards for user: {} (+{})", ctx.accounts.user_wallet.key(), amount);
+        Ok(())
+    }
+
+    /// Update project configuration
+    pub fn update_project_config(
+        ctx: Context<UpdateProject>,
+        tgem_plus_enabled: Option<bool>,
+        restrict_user_close: Option<bool>,
+        activity_capacity: Option<u16>,
+    ) -> Result<()> {
+        let project = &mut ctx.accounts.project;
+        let old_tgem_plus_enabled = project.tgem_plus_enabled;
+        let old_restrict_user_close = project.restrict_user_close;
+        let old_activity_capacity = project.activity_capacity;
+        
+        if let Some(enabled) = tgem_plus_enabled {
+            project.tgem_plus_enabled = enabled;
+            msg!("TGEM+ enabled: {}", enabled);
+        }
+
+        if let Some(restrict) = restrict_user_close {
+            project.restrict_user_close = restrict;
+            msg!("Restrict user close: {}", restrict);
+        }
+
+        if let Some(capacity) = activity_capacity {
+            require!(
+                capacity <= UserActivity::MAX_CAPACITY,
+                ErrorCode::ActivityCapacityTooLarge
+            );
+            project.activity_capacity = capacity;
+            msg!("Activity capacity: {}", capacity);
+        }
+
+        emit_cpi!(ProjectConfigUpdated {
+            project: project.key(),
+            old_tgem_plus_enabled,
+            new_tgem_plus_enabled: project.tgem_plus_enabled,
+            old_restrict_user_close,
+            new_restrict_user_close: project.restrict_user_close,
+            old_activity_capacity,
+            new_activity_capacity: project.activity_capacity,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Propose a new project authority (takes effect once accepted by the new key)
+    pub fn transfer_project_authority(
+        ctx: Context<UpdateProject>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let project = &mut ctx.accounts.project;
+        project.pending_authority = Some(new_authority);
+
+        emit_cpi!(ProjectAuthorityTransferProposed {
+            project: project.key(),
+            authority: project.authority,
+            pending_authority: new_authority,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Proposed project authority transfer to: {}", new_authority);
+        Ok(())
+    }
+
+    /// Accept a pending project authority transfer (signed by the new authority)
+    pub fn accept_project_authority(ctx: Context<AcceptProjectAuthority>) -> Result<()> {
+        let project = &mut ctx.accounts.project;
+        let old_authority = project.authority;
+        project.authority = ctx.accounts.new_authority.key();
+        project.pending_authority = None;
+
+        emit_cpi!(ProjectAuthorityTransferred {
+            project: project.key(),
+            old_authority,
+            new_authority: project.authority,
+            actor: ctx.accounts.new_authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Transferred project authority to: {}", project.authority);
+        Ok(())
+    }
+
+    /// Cancel a pending project authority transfer
+    pub fn cancel_project_authority_transfer(ctx: Context<UpdateProject>) -> Result<()> {
+        let project = &mut ctx.accounts.project;
+        let pending_authority = project
+            .pending_authority
+            .take()
+            .ok_or(ErrorCode::NoPendingAuthority)?;
+
+        emit_cpi!(ProjectAuthorityTransferCancelled {
+            project: project.key(),
+            authority: project.authority,
+            pending_authority,
+            actor: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Cancelled project authority transfer to: {}", pending_authority);
+        Ok(())
+    }
+
+    /// Create an M-of-N admin council that can hold `authority` roles
+    pub fn create_council(
+        ctx: Context<CreateCouncil>,
+        create_key: Pubkey,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        council::validate_council(&members, threshold)?;
+
+        let council = &mut ctx.accounts.council;
+        council.create_key = create_key;
+        council.members = members;
+        council.threshold = threshold;
+        council.proposal_count = 0;
+        council.bump = ctx.bumps.council;
+
+        emit_cpi!(CouncilUpdated {
+            council: council.key(),
+            members: council.members.clone(),
+            threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Council created: {} ({}-of-{})", council.key(), threshold, council.members.len());
+        Ok(())
+    }
+
+    /// Replace council members and threshold (executed by the council itself)
+    pub fn update_council(
+        ctx: Context<UpdateCouncil>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        council::validate_council(&members, threshold)?;
+
+        let council = &mut ctx.accounts.council;
+        council.members = members;
+        council.threshold = threshold;
+
+        emit_cpi!(CouncilUpdated {
+            council: council.key(),
+            members: council.members.clone(),
+            threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Council updated: {}-of-{}", threshold, council.members.len());
+        Ok(())
+    }
+
+    /// Propose an admin instruction for the council to sign (member only)
+    pub fn propose_council_action(
+        ctx: Context<ProposeCouncilAction>,
+        instruction_data: Vec<u8>,
+        accounts: Vec<CouncilAccountMeta>,
+    ) -> Result<()> {
+        council::validate_proposal(&instruction_data, &accounts)?;
+
+        let council = &mut ctx.accounts.council;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.council = council.key();
+        proposal.index = council.proposal_count;
+        proposal.proposer = ctx.accounts.member.key();
+        proposal.instruction_data = instruction_data;
+        proposal.accounts = accounts;
+        proposal.approvals = vec![ctx.accounts.member.key()];
+        proposal.bump = ctx.bumps.proposal;
+
+        council.proposal_count = council.proposal_count.checked_add(1).unwrap();
+
+        emit_cpi!(CouncilProposalCreated {
+            council: council.key(),
+            proposal: proposal.key(),
+            index: proposal.index,
+            proposer: proposal.proposer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Council proposal {} created", proposal.index);
+        Ok(())
+    }
+
+    /// Approve a pending council proposal (member only)
+    pub fn approve_council_action(ctx: Context<ApproveCouncilAction>) -> Result<()> {
+        let member = ctx.accounts.member.key();
+        let proposal = &mut ctx.accounts.proposal;
+        let approvals = council::approve(&mut proposal.approvals, member)?;
+
+        emit_cpi!(CouncilProposalApproved {
+            council: proposal.council,
+            proposal: proposal.key(),
+            member,
+            approvals,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Council proposal {} approved by {}", proposal.index, member);
+        Ok(())
+    }
+
+    /// Execute an approved proposal, signing as the council, then close it
+    /// to the proposer (member only)
+    pub fn execute_council_action<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteCouncilAction<'info>>,
+    ) -> Result<()> {
+        let council = &ctx.accounts.council;
+        let proposal = &ctx.accounts.proposal;
+        council::check_threshold(&proposal.approvals, &council.members, council.threshold)?;
+
+        let ix = council::proposal_instruction(
+            crate::ID,
+            &proposal.accounts,
+            &proposal.instruction_data,
+            ctx.remaining_accounts,
+        )?;
+        let seeds: &[&[u8]] = &[b"council", council.create_key.as_ref(), &[council.bump]];
+        invoke_signed(&ix, ctx.remaining_accounts, &[seeds])?;
+
+        emit_cpi!(CouncilProposalExecuted {
+            council: council.key(),
+            proposal: proposal.key(),
+            index: proposal.index,
+            executor: ctx.accounts.member.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Council proposal {} executed", proposal.index);
+        Ok(())
+    }
+
+    /// Withdraw a proposal before execution, refunding its rent (proposer only)
+    pub fn cancel_council_action(ctx: Context<CancelCouncilAction>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+
+        emit_cpi!(CouncilProposalCancelled {
+            council: proposal.council,
+            proposal: proposal.key(),
+            index: proposal.index,
+            proposer: proposal.proposer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Council proposal {} cancelled", proposal.index);
+        Ok(())
+    }
+
+    /// Upgrade a baseline `Project` to the current layout, growing it
+    /// to `Project::LEN` (permissionless, payer covers the extra rent)
+    pub fn migrate_project(ctx: Context<MigrateProject>) -> Result<()> {
+        let info = ctx.accounts.project.to_account_info();
+        match info.data_len() {
+            len if len == 8 + ProjectV0::LEN => {}
+            len if len == 8 + Project::LEN => return err!(ErrorCode::AlreadyMigrated),
+            _ => return err!(ErrorCode::UnknownLayout),
+        }
+        let legacy: ProjectV0 = read_legacy_account(&info, &Project::DISCRIMINATOR)?;
+
+        realloc_account(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + Project::LEN,
+        )?;
+        let project = legacy.into_current();
+        project.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        emit!(AccountMigrated {
+            account: info.key(),
+            version: Project::VERSION,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Project migrated to v{}: {}", Project::VERSION, project.project_key);
+        Ok(())
+    }
+
+    /// Upgrade a baseline `User` to the current layout, growing it
+    /// to `User::LEN` (permissionless, payer covers the extra rent)
+    pub fn migrate_user(ctx: Context<MigrateUser>) -> Result<()> {
+        let info = ctx.accounts.user.to_account_info();
+        match info.data_len() {
+            len if len == 8 + UserV0::LEN => {}
+            len if len == 8 + User::LEN => return err!(ErrorCode::AlreadyMigrated),
+            _ => return err!(ErrorCode::UnknownLayout),
+        }
+        let legacy: UserV0 = read_legacy_account(&info, &User::DISCRIMINATOR)?;
+
+        realloc_account(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + User::LEN,
+        )?;
+        let user = legacy.into_current();
+        user.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        emit!(AccountMigrated {
+            account: info.key(),
+            version: User::VERSION,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("User migrated to v{}: {}", User::VERSION, user.wallet);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(project_key: String)]
+pub struct InitializeProject<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Project::LEN,
+        seeds = [b"project", project_key.as_bytes()],
+        bump
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(mut, seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ProjectRegistryPage::LEN,
+        seeds = [
+            b"project_registry",
+            ProjectRegistryPage::page_for(protocol_config.project_count).to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub registry_page: Account<'info, ProjectRegistryPage>,
+    
+    /// CHECK: Must be empty so a new project can't shadow another project's alias
+    #[account(
+        seeds = [b"project_alias", project_key.as_bytes()],
+        bump,
+        constraint = key_alias.data_is_empty() @ ErrorCode::ProjectKeyTaken
+    )]
+    pub key_alias: UncheckedAccount<'info>,
+    
+    /// Required when `creation_policy` is `Allowlist`
+    #[account(seeds = [b"creator_allowance", authority.key().as_ref()], bump = creator_allowance.bump)]
+    pub creator_allowance: Option<Account<'info, CreatorAllowance>>,
+    
+    /// CHECK: Fee destination, required when `creation_policy` is `Fee`
+    #[account(mut, address = protocol_config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProtocolConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ProtocolConfig::LEN,
+        seeds = [b"protocol_config"],
+        bump
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::TherasProtocol>,
+    
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateProtocolConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct AllowProjectCreator<'info> {
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CreatorAllowance::LEN,
+        seeds = [b"creator_allowance", creator.as_ref()],
+        bump
+    )]
+    pub creator_allowance: Account<'info, CreatorAllowance>,
+    
+    pub admin: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RevokeProjectCreator<'info> {
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_allowance", creator_allowance.creator.as_ref()],
+        bump = creator_allowance.bump,
+        close = admin
+    )]
+    pub creator_allowance: Account<'info, CreatorAllowance>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProtocolAdminProject<'info> {
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    #[account(mut)]
+    pub project: Account<'info, Project>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReclaimProject<'info> {
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+        has_one = treasury @ ErrorCode::InvalidTreasury
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    #[account(mut, close = treasury)]
+    pub project: Account<'info, Project>,
+    
+    /// Required unless the project predates the registry
+    #[account(
+        mut,
+        seeds = [
+            b"project_registry",
+            ProjectRegistryPage::page_for(project.registry_index).to_le_bytes().as_ref()
+        ],
+        bump = registry_page.bump
+    )]
+    pub registry_page: Option<Account<'info, ProjectRegistryPage>>,
+    
+    /// CHECK: Rent destination, pinned to the configured treasury
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReclaimProjectAlias<'info> {
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+        has_one = treasury @ ErrorCode::InvalidTreasury
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    #[account(mut)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"project_alias", project_alias.alias.as_bytes()],
+        bump = project_alias.bump,
+        has_one = project @ ErrorCode::InvalidProject,
+        close = treasury
+    )]
+    pub project_alias: Account<'info, ProjectAlias>,
+    
+    /// CHECK: Rent destination, pinned to the configured treasury
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct ReclaimClosedUser<'info> {
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    #[account(mut)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"closed_user", project.key().as_ref(), wallet.as_ref()],
+        bump = closed_user.bump,
+        close = rent_payer
+    )]
+    pub closed_user: Account<'info, ClosedUser>,
+    
+    /// CHECK: Receives the refunded rent, pinned to the tombstone's payer
+    #[account(mut, address = closed_user.rent_payer)]
+    pub rent_payer: UncheckedAccount<'info>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(alias: String)]
+pub struct AddProjectAlias<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == project.authority @ ErrorCode::Unauthorized
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ProjectAlias::LEN,
+        seeds = [b"project_alias", alias.as_bytes()],
+        bump
+    )]
+    pub project_alias: Account<'info, ProjectAlias>,
+    
+    /// CHECK: Must be empty so an alias can't shadow another project's primary key
+    #[account(
+        seeds = [b"project", alias.as_bytes()],
+        bump,
+        constraint = key_project.data_is_empty() @ ErrorCode::ProjectKeyTaken
+    )]
+    pub key_project: UncheckedAccount<'info>,
+    
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveProjectAlias<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == project.authority @ ErrorCode::Unauthorized
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"project_alias", project_alias.alias.as_bytes()],
+        bump = project_alias.bump,
+        has_one = project @ ErrorCode::InvalidProject,
+        close = authority
+    )]
+    pub project_alias: Account<'info, ProjectAlias>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSunsetUser<'info> {
+    #[account(mut, constraint = project.closing @ ErrorCode::ProjectNotClosing)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", project.key().as_ref(), user.wallet.as_ref()],
+        bump = user.bump,
+        has_one = project @ ErrorCode::InvalidProject,
+        has_one = rent_payer,
+        close = rent_payer
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        mut,
+        seeds = [b"event_counters", user.key().as_ref()],
+        bump = event_counters.bump,
+        close = rent_payer
+    )]
+    pub event_counters: Option<Account<'info, UserEventCounters>>,
+    
+    /// Closed along with the user when `rent_payer` paid for it
+    #[account(
+        mut,
+        seeds = [b"user_activity", user.key().as_ref()],
+        bump = activity.load()?.bump,
+        constraint = activity.load()?.rent_payer == rent_payer.key(),
+        close = rent_payer
+    )]
+    pub activity: Option<AccountLoader<'info, UserActivity>>,
+    
+    /// Only while the season is live; frozen stats go through `close_season_stats`
+    #[account(
+        mut,
+        seeds = [
+            b"season_stats",
+            project.key().as_ref(),
+            project.current_season.to_le_bytes().as_ref(),
+            user.key().as_ref()
+        ],
+        bump = season_stats.bump,
+        constraint = project.live_season(Clock::get()?.unix_timestamp).is_some() @ ErrorCode::SeasonNotLive,
+        close = rent_payer
+    )]
+    pub season_stats: Option<Account<'info, SeasonStats>>,
+    
+    /// CHECK: Receives the refunded rent, pinned to `user.rent_payer`
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    
+    pub cranker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct CloseSunsetTombstone<'info> {
+    #[account(mut, constraint = project.closing @ ErrorCode::ProjectNotClosing)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"closed_user", project.key().as_ref(), wallet.as_ref()],
+        bump = closed_user.bump,
+        close = rent_payer
+    )]
+    pub closed_user: Account<'info, ClosedUser>,
+    
+    /// CHECK: Receives the refunded rent, pinned to the tombstone's payer
+    #[account(mut, address = closed_user.rent_payer)]
+    pub rent_payer: UncheckedAccount<'info>,
+    
+    pub cranker: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseProject<'info> {
+    #[account(
+        mut,
+        constraint = project.closing @ ErrorCode::ProjectNotClosing,
+        constraint = authority.key() == project.authority @ ErrorCode::Unauthorized,
+        close = authority
+    )]
+    pub project: Account<'info, Project>,
+    
+    /// Required unless the project predates the registry
+    #[account(
+        mut,
+        seeds = [
+            b"project_registry",
+            ProjectRegistryPage::page_for(project.registry_index).to_le_bytes().as_ref()
+        ],
+        bump = registry_page.bump
+    )]
+    pub registry_page: Option<Account<'info, ProjectRegistryPage>>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterUser<'info> {
+    #[account(
+        mut,
+        constraint = !project.closing @ ErrorCode::ProjectClosing,
+        constraint = project.version == Project::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        init,
+        payer = user_wallet,
+        space = 8 + User::LEN,
+        seeds = [b"user", project.key().as_ref(), user_wallet.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+    
+    /// Opened by the day's first registration or event at `payer`'s expense
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + DailyStats::LEN,
+        seeds = [b"daily_stats", project.key().as_ref(), DailyStats::today()?.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    /// CHECK: The wallet's `ClosedUser` tombstone, read if it registered before
+    #[account(seeds = [b"closed_user", project.key().as_ref(), user_wallet.key().as_ref()], bump)]
+    pub closed_user: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub user_wallet: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseDailyStats<'info> {
+    #[account(constraint = authority.key() == project.authority @ ErrorCode::Unauthorized)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"daily_stats", project.key().as_ref(), daily_stats.day.to_le_bytes().as_ref()],
+        bump = daily_stats.bump,
+        has_one = project @ ErrorCode::InvalidProject,
+        constraint = daily_stats.day < DailyStats::today()? @ ErrorCode::DayNotOver,
+        close = authority
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RecordEventsBatch<'info> {
+    #[account(
+        mut,
+        seeds = [b"project", project.project_key.as_bytes()],
+        bump = project.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        constraint = !project.closing @ ErrorCode::ProjectClosing,
+        constraint = project.version == Project::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub project: Account<'info, Project>,
+    
+    /// Required once the project has a tier config
+    #[account(seeds = [b"tier_config", project.key().as_ref()], bump = tier_config.bump)]
+    pub tier_config: Option<Account<'info, TierConfig>>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + DailyStats::LEN,
+        seeds = [b"daily_stats", project.key().as_ref(), DailyStats::today()?.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitUserActivity<'info> {
+    #[account(constraint = project.activity_capacity > 0 @ ErrorCode::ActivityDisabled)]
+    pub project: Account<'info, Project>,
+    
+    #[account(mut, has_one = project @ ErrorCode::InvalidProject)]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = UserActivity::space(project.activity_capacity),
+        seeds = [b"user_activity", user.key().as_ref()],
+        bump
+    )]
+    pub activity: AccountLoader<'info, UserActivity>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseUserActivity<'info> {
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", project.key().as_ref(), user_wallet.key().as_ref()],
+        bump = user.bump,
+        has_one = project @ ErrorCode::InvalidProject
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_activity", user.key().as_ref()],
+        bump = activity.load()?.bump,
+        constraint = activity.load()?.rent_payer == rent_payer.key(),
+        close = rent_payer
+    )]
+    pub activity: AccountLoader<'info, UserActivity>,
+    
+    /// CHECK: Receives the refunded rent, pinned to `activity.rent_payer`
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    
+    pub user_wallet: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct StartSeason<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == project.authority @ ErrorCode::Unauthorized,
+        constraint = !project.season_open @ ErrorCode::SeasonOpen
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Season::LEN,
+        seeds = [
+            b"season",
+            project.key().as_ref(),
+            (project.current_season + 1).to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub season: Account<'info, Season>,
+    
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseSeason<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == project.authority @ ErrorCode::Unauthorized,
+        constraint = project.season_open @ ErrorCode::NoOpenSeason
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"season", project.key().as_ref(), project.current_season.to_le_bytes().as_ref()],
+        bump = season.bump
+    )]
+    pub season: Account<'info, Season>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSeasonAccount<'info> {
+    #[account(constraint = authority.key() == project.authority @ ErrorCode::Unauthorized)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"season", project.key().as_ref(), season.number.to_le_bytes().as_ref()],
+        bump = season.bump,
+        constraint = season.closed_at != 0 @ ErrorCode::SeasonOpen,
+        close = authority
+    )]
+    pub season: Account<'info, Season>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSeasonStats<'info> {
+    #[account(constraint = authority.key() == project.authority @ ErrorCode::Unauthorized)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"season_stats",
+            project.key().as_ref(),
+            season_stats.season.to_le_bytes().as_ref(),
+            season_stats.user.as_ref()
+        ],
+        bump = season_stats.bump,
+        constraint = season_stats.season != project.current_season || !project.season_open
+            @ ErrorCode::SeasonOpen,
+        close = authority
+    )]
+    pub season_stats: Account<'info, SeasonStats>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseLeaderboard<'info> {
+    #[account(constraint = authority.key() == project.authority @ ErrorCode::Unauthorized)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        constraint = leaderboard.load()?.project == project.key() @ ErrorCode::InvalidLeaderboard,
+        close = authority
+    )]
+    pub leaderboard: AccountLoader<'info, Leaderboard>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(metric: LeaderboardMetric, season: u32, capacity: u32)]
+pub struct CreateLeaderboard<'info> {
+    #[account(constraint = authority.key() == project.authority @ ErrorCode::Unauthorized)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = Leaderboard::space(capacity),
+        seeds = [
+            b"leaderboard",
+            project.key().as_ref(),
+            [metric as u8].as_ref(),
+            season.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub leaderboard: AccountLoader<'info, Leaderboard>,
+    
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitLeaderboardScore<'info> {
+    #[account(
+        mut,
+        constraint = leaderboard.load()?.project == user.project @ ErrorCode::InvalidLeaderboard
+    )]
+    pub leaderboard: AccountLoader<'info, Leaderboard>,
+    
+    pub user: Account<'info, User>,
+    
+    /// Required for season leaderboards
+    #[account(
+        seeds = [
+            b"season_stats",
+            user.project.as_ref(),
+            season_stats.season.to_le_bytes().as_ref(),
+            user.key().as_ref()
+        ],
+        bump = season_stats.bump
+    )]
+    pub season_stats: Option<Account<'info, SeasonStats>>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct EvictLeaderboardEntry<'info> {
+    #[account(
+        mut,
+        constraint = leaderboard.load()?.project == project.key() @ ErrorCode::InvalidLeaderboard
+    )]
+    pub leaderboard: AccountLoader<'info, Leaderboard>,
+    
+    pub project: Account<'info, Project>,
+    
+    /// CHECK: The wallet's `User` PDA, which must be closed
+    #[account(
+        seeds = [b"user", project.key().as_ref(), wallet.as_ref()],
+        bump,
+        constraint = user.data_is_empty() @ ErrorCode::UserAlreadyRegistered
+    )]
+    pub user: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DisableTiers<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == project.authority @ ErrorCode::Unauthorized
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"tier_config", project.key().as_ref()],
+        bump = tier_config.bump,
+        close = authority
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetTierConfig<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == project.authority @ ErrorCode::Unauthorized
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + TierConfig::LEN,
+        seeds = [b"tier_config", project.key().as_ref()],
+        bump
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+    
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(event_id: u16)]
+pub struct CreateEventDefinition<'info> {
+    #[account(
+        constraint = authority.key() == project.authority @ ErrorCode::Unauthorized,
+        constraint = !project.closing @ ErrorCode::ProjectClosing,
+        constraint = project.version == Project::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + EventDefinition::LEN,
+        seeds = [b"event_definition", project.key().as_ref(), event_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub event_definition: Account<'info, EventDefinition>,
+    
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateEventDefinition<'info> {
+    #[account(
+        constraint = authority.key() == project.authority @ ErrorCode::Unauthorized,
+        constraint = !project.closing @ ErrorCode::ProjectClosing,
+        constraint = project.version == Project::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"event_definition", project.key().as_ref(), event_definition.event_id.to_le_bytes().as_ref()],
+        bump = event_definition.bump,
+        has_one = project
+    )]
+    pub event_definition: Account<'info, EventDefinition>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: u16)]
+pub struct RecordCustomEvent<'info> {
+    #[account(
+        mut,
+        seeds = [b"project", project.project_key.as_bytes()],
+        bump = project.bump,
+        constraint = !project.closing @ ErrorCode::ProjectClosing,
+        constraint = project.version == Project::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"event_definition", project.key().as_ref(), event_id.to_le_bytes().as_ref()],
+        bump = event_definition.bump,
+        has_one = project,
+        constraint = event_definition.active @ ErrorCode::EventDefinitionInactive
+    )]
+    pub event_definition: Account<'info, EventDefinition>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", project.key().as_ref(), user_wallet.key().as_ref()],
+        bump = user.bump,
+        has_one = project @ ErrorCode::InvalidProject,
+        constraint = user.version == User::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + UserEventCounters::LEN,
+        seeds = [b"event_counters", user.key().as_ref()],
+        bump
+    )]
+    pub event_counters: Account<'info, UserEventCounters>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + DailyStats::LEN,
+        seeds = [b"daily_stats", project.key().as_ref(), DailyStats::today()?.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    /// The user's activity buffer, required once they created one
+    #[account(
+        mut,
+        seeds = [b"user_activity", user.key().as_ref()],
+        bump = activity.load()?.bump
+    )]
+    pub activity: Option<AccountLoader<'info, UserActivity>>,
+    
+    /// Required once the project has a tier config
+    #[account(seeds = [b"tier_config", project.key().as_ref()], bump = tier_config.bump)]
+    pub tier_config: Option<Account<'info, TierConfig>>,
+    
+    /// Required while a season is live, and only created then
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + SeasonStats::LEN,
+        seeds = [
+            b"season_stats",
+            project.key().as_ref(),
+            project.current_season.to_le_bytes().as_ref(),
+            user.key().as_ref()
+        ],
+        bump,
+        constraint = season_stats.user != Pubkey::default()
+            || project.live_season(Clock::get()?.unix_timestamp).is_some()
+            @ ErrorCode::SeasonNotLive
+    )]
+    pub season_stats: Option<Account<'info, SeasonStats>>,
+    
+    /// CHECK: Bound to `user` through its seeds; must sign if the definition requires it
+    pub user_wallet: UncheckedAccount<'info>,
+    
+    /// Must sign if the definition requires the project authority
+    #[account(address = project.authority @ ErrorCode::Unauthorized)]
+    pub authority: Option<Signer<'info>>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateRentSponsor<'info> {
+    #[account(constraint = authority.key() == project.authority @ ErrorCode::Unauthorized)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RentSponsor::LEN,
+        seeds = [b"rent_sponsor", project.key().as_ref()],
+        bump
+    )]
+    pub rent_sponsor: Account<'info, RentSponsor>,
+    
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateRentSponsor<'info> {
+    #[account(constraint = authority.key() == project.authority @ ErrorCode::Unauthorized)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", project.key().as_ref()],
+        bump = rent_sponsor.bump
+    )]
+    pub rent_sponsor: Account<'info, RentSponsor>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundRentSponsor<'info> {
+    #[account(constraint = authority.key() == project.authority @ ErrorCode::Unauthorized)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", project.key().as_ref()],
+        bump = rent_sponsor.bump
+    )]
+    pub rent_sponsor: Account<'info, RentSponsor>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawRentSponsor<'info> {
+    #[account(constraint = authority.key() == project.authority @ ErrorCode::Unauthorized)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", project.key().as_ref()],
+        bump = rent_sponsor.bump
+    )]
+    pub rent_sponsor: Account<'info, RentSponsor>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRentSponsor<'info> {
+    #[account(constraint = authority.key() == project.authority @ ErrorCode::Unauthorized)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", project.key().as_ref()],
+        bump = rent_sponsor.bump,
+        close = authority
+    )]
+    pub rent_sponsor: Account<'info, RentSponsor>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterUserSponsored<'info> {
+    #[account(
+        mut,
+        constraint = !project.closing @ ErrorCode::ProjectClosing,
+        constraint = project.version == Project::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", project.key().as_ref()],
+        bump = rent_sponsor.bump
+    )]
+    pub rent_sponsor: Account<'info, RentSponsor>,
+    
+    /// CHECK: Uninitialized user PDA, created in the handler with sponsor lamports
+    #[account(
+        mut,
+        seeds = [b"user", project.key().as_ref(), user_wallet.key().as_ref()],
+        bump,
+        constraint = user.data_is_empty() @ ErrorCode::UserAlreadyRegistered
+    )]
+    pub user: UncheckedAccount<'info>,
+    
+    /// CHECK: Today's `DailyStats`, created with sponsor lamports if it doesn't exist yet
+    #[account(
+        mut,
+        seeds = [b"daily_stats", project.key().as_ref(), DailyStats::today()?.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub daily_stats: UncheckedAccount<'info>,
+    
+    /// CHECK: The wallet's `ClosedUser` tombstone, which must not exist
+    #[account(
+        seeds = [b"closed_user", project.key().as_ref(), user_wallet.key().as_ref()],
+        bump,
+        constraint = closed_user.data_is_empty() @ ErrorCode::AlreadySponsored
+    )]
+    pub closed_user: UncheckedAccount<'info>,
+    
+    pub user_wallet: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseUser<'info> {
+    #[account(mut)]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", project.key().as_ref(), user_wallet.key().as_ref()],
+        bump = user.bump,
+        has_one = project @ ErrorCode::InvalidProject,
+        has_one = rent_payer,
+        close = rent_payer
+    )]
+    pub user: Account<'info, User>,
+    
+    /// CHECK: `ClosedUser` tombstone keeping the cooldown, index and event
+    /// chain for a later re-registration; created from the user's rent
+    #[account(
+        mut,
+        seeds = [b"closed_user", project.key().as_ref(), user_wallet.key().as_ref()],
+        bump
+    )]
+    pub closed_user: UncheckedAccount<'info>,
+    
+    /// CHECK: The `ReferralBinding` that would tie this wallet to a referrer;
+    /// must not exist while `restrict_user_close` is set
+    #[account(seeds = [b"referral", project.key().as_ref(), user_wallet.key().as_ref()], bump)]
+    pub referral_binding: UncheckedAccount<'info>,
+    
+    /// Closed along with the user; custom event caps restart on re-registration
+    #[account(
+        mut,
+        seeds = [b"event_counters", user.key().as_ref()],
+        bump = event_counters.bump,
+        close = rent_payer
+    )]
+    pub event_counters: Option<Account<'info, UserEventCounters>>,
+    
+    /// Required while the user has one. Closed along with the user when
+    /// `rent_payer` paid for it; otherwise close it first with `close_user_activity`
+    #[account(
+        mut,
+        seeds = [b"user_activity", user.key().as_ref()],
+        bump = activity.load()?.bump,
+        constraint = activity.load()?.rent_payer == rent_payer.key(),
+        close = rent_payer
+    )]
+    pub activity: Option<AccountLoader<'info, UserActivity>>,
+    
+    /// The user's stats for the live season, closed along with the user; once
+    /// the season ends they are frozen and only `close_season_stats` removes them
+    #[account(
+        mut,
+        seeds = [
+            b"season_stats",
+            project.key().as_ref(),
+            project.current_season.to_le_bytes().as_ref(),
+            user.key().as_ref()
+        ],
+        bump = season_stats.bump,
+        constraint = project.live_season(Clock::get()?.unix_timestamp).is_some() @ ErrorCode::SeasonNotLive,
+        close = rent_payer
+    )]
+    pub season_stats: Option<Account<'info, SeasonStats>>,
+    
+    /// CHECK: Receives the refunded rent, pinned to `user.rent_payer`
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    
+    pub user_wallet: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(referred_user: Pubkey)]
+pub struct RecordReferral<'info> {
+    pub event: RecordEvent<'info>,
+    
+    /// A wallet can be referred once per project
+    #[account(
+        init,
+        payer = event.payer,
+        space = 8 + ReferralBinding::LEN,
+        seeds = [b"referral", event.project.key().as_ref(), referred_user.as_ref()],
+        bump
+    )]
+    pub binding: Account<'info, ReferralBinding>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseReferral<'info> {
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral", project.key().as_ref(), binding.referred.as_ref()],
+        bump = binding.bump,
+        has_one = project @ ErrorCode::InvalidProject,
+        has_one = referrer,
+        has_one = rent_payer,
+        close = rent_payer
+    )]
+    pub binding: Account<'info, ReferralBinding>,
+    
+    /// CHECK: The referrer's `User`, pinned to `binding.referrer`; may already be closed
+    #[account(mut)]
+    pub referrer: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives the refunded rent, pinned to `binding.rent_payer`
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    
+    /// The referrer's wallet or the project authority
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RecordEvent<'info> {
+    #[account(
+        mut,
+        seeds = [b"project", project.project_key.as_bytes()],
+        bump = project.bump,
+        constraint = !project.closing @ ErrorCode::ProjectClosing,
+        constraint = project.version == Project::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", project.key().as_ref(), user_wallet.key().as_ref()],
+        bump = user.bump,
+        has_one = project @ ErrorCode::InvalidProject,
+        constraint = user.wallet == user_wallet.key() @ ErrorCode::InvalidUser,
+        constraint = user.version == User::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub user: Account<'info, User>,
+    
+    /// The user's activity buffer, required once they created one
+    #[account(
+        mut,
+        seeds = [b"user_activity", user.key().as_ref()],
+        bump = activity.load()?.bump
+    )]
+    pub activity: Option<AccountLoader<'info, UserActivity>>,
+    
+    /// Required once the project has a tier config
+    #[account(seeds = [b"tier_config", project.key().as_ref()], bump = tier_config.bump)]
+    pub tier_config: Option<Account<'info, TierConfig>>,
+    
+    /// Required while a season is live, and only created then
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + SeasonStats::LEN,
+        seeds = [
+            b"season_stats",
+            project.key().as_ref(),
+            project.current_season.to_le_bytes().as_ref(),
+            user.key().as_ref()
+        ],
+        bump,
+        constraint = season_stats.user != Pubkey::default()
+            || project.live_season(Clock::get()?.unix_timestamp).is_some()
+            @ ErrorCode::SeasonNotLive
+    )]
+    pub season_stats: Option<Account<'info, SeasonStats>>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + DailyStats::LEN,
+        seeds = [b"daily_stats", project.key().as_ref(), DailyStats::today()?.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    pub user_wallet: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateTGEM<'info> {
+    #[account(
+        seeds = [b"project", project.project_key.as_bytes()],
+        bump = project.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        constraint = project.version == Project::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub project: Account<'info, Project>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", project.key().as_ref(), user_wallet.key().as_ref()],
+        bump = user.bump,
+        has_one = project @ ErrorCode::InvalidProject,
+        constraint = user.wallet == user_wallet.key() @ ErrorCode::InvalidUser,
+        constraint = user.version == User::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub user: Account<'info, User>,
+    
+    /// CHECK: Only used as a seed; `user` must be this wallet's registration
+    pub user_wallet: AccountInfo<'info>,
+    
+    /// Required once the project has a tier config
+    #[account(seeds = [b"tier_config", project.key().as_ref()], bump = tier_config.bump)]
+    pub tier_config: Option<Account<'info, TierConfig>>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateProject<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == project.authority @ ErrorCode::Unauthorized,
+        constraint = project.version == Project::VERSION @ ErrorCode::AccountNotMigrated
+    )]
+    pub project: Account<'info, Project>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptProjectAuthority<'info> {
+    #[account(
+        mut,
+        constraint = project.pending_authority == Some(new_authority.key()) @ ErrorCode::Unauthorized
+    )]
+    pub project: Account<'info, Project>,
+    
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProject<'info> {
+    /// CHECK: Legacy `Project`; discriminator and length checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub project: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateUser<'info> {
+    /// CHECK: Legacy `User`; discriminator and length checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub user: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(create_key: Pubkey)]
+pub struct CreateCouncil<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AdminCouncil::LEN,
+        seeds = [b"council", create_key.as_ref()],
+        bump
+    )]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateCouncil<'info> {
+    /// Must sign via `execute_council_action`
+    #[account(
+        mut,
+        signer,
+        seeds = [b"council", council.create_key.as_ref()],
+        bump = council.bump
+    )]
+    pub council: Account<'info, AdminCouncil>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeCouncilAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"council", council.create_key.as_ref()],
+        bump = council.bump
+    )]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(
+        init,
+        payer = member,
+        space = 8 + CouncilProposal::LEN,
+        seeds = [b"council_proposal", council.key().as_ref(), council.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+    
+    #[account(mut, constraint = council.members.contains(&member.key()) @ CouncilError::NotCouncilMember)]
+    pub member: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ApproveCouncilAction<'info> {
+    #[account(seeds = [b"council", council.create_key.as_ref()], bump = council.bump)]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(
+        mut,
+        seeds = [b"council_proposal", council.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+    
+    #[account(constraint = council.members.contains(&member.key()) @ CouncilError::NotCouncilMember)]
+    pub member: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteCouncilAction<'info> {
+    /// Read-only here: the proposed instruction may itself rewrite the council
+    #[account(seeds = [b"council", council.create_key.as_ref()], bump = council.bump)]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(
+        mut,
+        seeds = [b"council_proposal", council.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        close = proposer
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+    
+    /// CHECK: Rent refund destination, pinned to the proposer who paid it
+    #[account(mut, address = proposal.proposer)]
+    pub proposer: AccountInfo<'info>,
+    
+    #[account(constraint = council.members.contains(&member.key()) @ CouncilError::NotCouncilMember)]
+    pub member: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelCouncilAction<'info> {
+    #[account(seeds = [b"council", council.create_key.as_ref()], bump = council.bump)]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(
+        mut,
+        seeds = [b"council_proposal", council.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        close = proposer
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+    
+    #[account(mut, address = proposal.proposer @ CouncilError::NotProposer)]
+    pub proposer: Signer<'info>,
+}
+
+#[account]
+pub struct ProtocolConfig {
+    pub admin: Pubkey,                    // 32
+    pub creation_policy: CreationPolicy,  // 1
+    pub treasury: Pubkey,                 // 32
+    pub creation_fee: u64,                // 8 (lamports)
+    pub project_count: u64,               // 8
+    pub bump: u8,                         // 1
+}
+
+impl ProtocolConfig {
+    pub const LEN: usize = 32 + 1 + 32 + 8 + 8 + 1;
+}
+
+/// Fixed-size page of project addresses, in creation order. Closed or
+/// reclaimed projects leave `Pubkey::default()` in their slot
+#[account]
+pub struct ProjectRegistryPage {
+    pub page: u32,                // 4
+    pub projects: Vec<Pubkey>,    // 4 + 32 * CAPACITY
+    pub bump: u8,                 // 1
+}
+
+impl ProjectRegistryPage {
+    pub const CAPACITY: u64 = 64;
+    pub const LEN: usize = 4 + 4 + 32 * Self::CAPACITY as usize + 1;
+
+    pub fn page_for(registry_index: u64) -> u32 {
+        (registry_index / Self::CAPACITY) as u32
+    }
+}
+
+#[account]
+pub struct CreatorAllowance {
+    pub creator: Pubkey,          // 32
+    pub bump: u8,                 // 1
+}
+
+impl CreatorAllowance {
+    pub const LEN: usize = 32 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CreationPolicy {
+    Open,
+    Allowlist,
+    Fee,
+}
+
+#[account]
+pub struct Project {
+    pub authority: Pubkey,        // 32
+    pub pending_authority: Option<Pubkey>, // 1 + 32
+    pub project_key: String,      // 4 + 32 = 36
+    pub tgem_plus_enabled: bool,  // 1
+    pub total_users: u64,         // 8
+    pub total_events: u64,        // 8
+    pub bump: u8,                 // 1
+    pub display_name: String,     // 4 + 32 = 36
+    pub uri: String,              // 4 + 200 = 204
+    pub category: ProjectCategory, // 1
+    pub logo_hash: [u8; 32],      // 32
+    pub verified: bool,           // 1
+    pub registry_index: u64,      // 8
+    pub alias_count: u8,          // 1
+    pub closing: bool,            // 1
+    pub restrict_user_close: bool, // 1
+    pub version: u8,              // 1
+    /// Ring buffer size for new `UserActivity` accounts (0 = no history)
+    pub activity_capacity: u16,   // 2
+    /// Lifetime registrations, also the source of `User::user_index`;
+    /// re-registering after `close_user` doesn't count again
+    pub users_registered: u64,    // 8
+    /// Set while a `TierConfig` exists; `record_*` must then pass it
+    pub tiers_enabled: bool,      // 1
+    /// Number of the latest `Season` (0 = none yet); its window is cached below
+    pub current_season: u32,      // 4
+    pub season_start: i64,        // 8
+    pub season_end: i64,          // 8
+    pub season_open: bool,        // 1
+    /// `ClosedUser` tombstones still open; they must all be closed before the
+    /// project is, or a project re-created at this address would resume them
+    pub closed_users: u32,        // 4
+    /// Zeroed space for future fields, so adding one doesn't need a realloc
+    pub reserved: [u8; 92],       // 92
+}
+
+impl Project {
+    pub const VERSION: u8 = 1;
+    pub const RESERVED_LEN: usize = 92;
+    pub const MAX_DISPLAY_NAME_LEN: usize = 32;
+    pub const MAX_URI_LEN: usize = 200;
+    pub const MAX_KEY_LEN: usize = 32;
+    pub const MAX_ALIASES: u8 = 8;
+    /// `registry_index` of migrated projects, which predate the registry
+    pub const UNREGISTERED: u64 = u64::MAX;
+    pub const LEN: usize = 32 + 33 + 36 + 1 + 8 + 8 + 1 + 36 + 204 + 1 + 32 + 1 + 8 + 1 + 1 + 1 + 1 + 2 + 8 + 1 + 4 + 8 + 8 + 1 + 4 + 92;
+
+    /// Season that events at `timestamp` count towards, if any
+    pub fn live_season(&self, timestamp: i64) -> Option<u32> {
+        (self.season_open && timestamp >= self.season_start && timestamp < self.season_end)
+            .then_some(self.current_season)
+    }
+
+    /// Count a registration, returning the user's `user_index`: a new one,
+    /// or the one from `closed` when the wallet is returning
+    pub fn admit_user(&mut self, closed: Option<&ClosedUser>) -> u64 {
+        self.total_users = self.total_users.saturating_add(1);
+        match closed {
+            Some(closed) => closed.user_index,
+            None => {
+                self.users_registered = self.users_registered.saturating_add(1);
+                self.users_registered
+            }
+        }
+    }
+}
+
+/// Additional key for a project. Clients resolve the alias PDA to `project`,
+/// so `register_user` and `record_*` land on the same `Project` and `User` accounts
+#[account]
+pub struct ProjectAlias {
+    pub project: Pubkey,          // 32
+    pub alias: String,            // 4 + 32 = 36
+    pub bump: u8,                 // 1
+}
+
+impl ProjectAlias {
+    pub const LEN: usize = 32 + 36 + 1;
+}
+
+/// Project keys are canonical: 1-32 chars of uppercase A-Z, 0-9, '_' or '-'
+fn validate_project_key(key: &str) -> Result<()> {
+    require!(key.len() <= Project::MAX_KEY_LEN, ErrorCode::ProjectKeyTooLong);
+    require!(
+        !key.is_empty()
+            && key
+                .bytes()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_' || b == b'-'),
+        ErrorCode::InvalidProjectKey
+    );
+    Ok(())
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectCategory {
+    Gaming,
+    DeFi,
+    Nft,
+    Social,
+    Commerce,
+    Other,
+}
+
+#[account]
+pub struct User {
+    pub project: Pubkey,          // 32
+    pub wallet: Pubkey,           // 32
+    pub daily_logins: u64,        // 8
+    pub quests: u64,              // 8
+    pub referrals: u64,           // 8
+    pub total_tgem_earned: u64,   // 8
+    pub last_login: i64,          // 8
+    pub bump: u8,                 // 1
+    pub rent_payer: Pubkey,       // 32
+    pub pending_rewards: u64,     // 8
+    pub version: u8,              // 1
+    /// Head of the hash chain over every recorded event (zero before the first)
+    pub event_chain_hash: [u8; 32], // 32
+    /// Number of recorded events
+    pub event_seq: u64,           // 8
+    /// 1-based registration number within the project (0 = registered before indexing)
+    pub user_index: u64,          // 8
+    /// Day index of the user's last recorded event, for `DailyStats::active_users`
+    pub last_active_day: i64,     // 8
+    pub xp: u64,                  // 8
+    /// Index into the project's `TierConfig` tiers (0 = base tier) as of the
+    /// user's last XP change; `TierConfig::tier_for(xp)` is authoritative
+    pub tier: u8,                 // 1
+    /// Unreleased `ReferralBinding`s with this user as referrer
+    pub referral_bindings: u32,   // 4
+    /// Set while a `UserActivity` exists; `record_*` must then pass it
+    pub has_activity: bool,       // 1
+    /// Zeroed space for future fields, so adding one doesn't need a realloc
+    pub reserved: [u8; 58],       // 58
+}
+
+impl User {
+    pub const VERSION: u8 = 1;
+    pub const RESERVED_LEN: usize = 58;
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 32 + 8 + 1 + 32 + 8 + 8 + 8 + 8 + 1 + 4 + 1 + 58;
+
+    pub fn new(
+        project: Pubkey,
+        wallet: Pubkey,
+        rent_payer: Pubkey,
+        user_index: u64,
+        bump: u8,
+    ) -> Self {
+        Self {
+            project,
+            wallet,
+            daily_logins: 0,
+            quests: 0,
+            referrals: 0,
+            total_tgem_earned: 0,
+            last_login: 0,
+            bump,
+            rent_payer,
+            pending_rewards: 0,
+            version: Self::VERSION,
+            event_chain_hash: [0; 32],
+            event_seq: 0,
+            user_index,
+            last_active_day: 0,
+            xp: 0,
+            tier: 0,
+            referral_bindings: 0,
+            has_activity: false,
+            reserved: [0; Self::RESERVED_LEN],
+        }
+    }
+
+    /// Carry over what a previous registration left in its tombstone, so
+    /// closing and re-registering can't skip the login cooldown or restart
+    /// the event chain
+    pub fn resume(&mut self, closed: &ClosedUser) {
+        self.last_login = closed.last_login;
+        self.last_active_day = closed.last_active_day;
+        self.event_seq = closed.event_seq;
+        self.event_chain_hash = closed.event_chain_hash;
+    }
+
+    /// Extend the event hash chain, returning the new sequence number and head:
+    /// keccak(prev_head || seq_le || kind || custom_event_id_le || payload_hash
+    /// || count_le || timestamp_le), with `kind` and `custom_event_id` from `EventType::code`
+    pub fn chain_event(
+        &mut self,
+        event_type: &EventType,
+        payload_hash: &[u8; 32],
+        count: u64,
+        timestamp: i64,
+    ) -> (u64, [u8; 32]) {
+        let (kind, custom_event_id) = event_type.code();
+        self.event_seq = self.event_seq.saturating_add(1);
+        self.event_chain_hash = keccak::hashv(&[
+            &self.event_chain_hash,
+            &self.event_seq.to_le_bytes(),
+            &[kind],
+            &custom_event_id.to_le_bytes(),
+            payload_hash,
+            &count.to_le_bytes(),
+            &timestamp.to_le_bytes(),
+        ])
+        .to_bytes();
+        (self.event_seq, self.event_chain_hash)
+    }
+}
+
+/// `Project` as first deployed, decoded by `migrate_project`
+#[derive(AnchorDeserialize)]
+struct ProjectV0 {
+    authority: Pubkey,
+    project_key: String,
+    tgem_plus_enabled: bool,
+    total_users: u64,
+    total_events: u64,
+    bump: u8,
+}
+
+impl ProjectV0 {
+    const LEN: usize = 32 + 36 + 1 + 8 + 8 + 1;
+
+    fn into_current(self) -> Project {
+        Project {
+            authority: self.authority,
+            pending_authority: None,
+            project_key: self.project_key,
+            tgem_plus_enabled: self.tgem_plus_enabled,
+            total_users: self.total_users,
+            total_events: self.total_events,
+            bump: self.bump,
+            display_name: String::new(),
+            uri: String::new(),
+            category: ProjectCategory::Other,
+            logo_hash: [0; 32],
+            verified: false,
+            registry_index: Project::UNREGISTERED,
+            alias_count: 0,
+            closing: false,
+            restrict_user_close: false,
+            version: Project::VERSION,
+            activity_capacity: 0,
+            // Baseline users could not close, so `total_users` is the lifetime count.
+            // Migrated users keep `user_index` 0; new ones are numbered after them
+            users_registered: self.total_users,
+            tiers_enabled: false,
+            current_season: 0,
+            season_start: 0,
+            season_end: 0,
+            season_open: false,
+            closed_users: 0,
+            reserved: [0; Project::RESERVED_LEN],
+        }
+    }
+}
+
+/// `User` as first deployed, decoded by `migrate_user`
+#[derive(AnchorDeserialize)]
+struct UserV0 {
+    project: Pubkey,
+    wallet: Pubkey,
+    daily_logins: u64,
+    quests: u64,
+    referrals: u64,
+    total_tgem_earned: u64,
+    last_login: i64,
+    bump: u8,
+}
+
+impl UserV0 {
+    const LEN: usize = 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    /// Baseline registrations were paid by the wallet, so it receives the rent on close
+    fn into_current(self) -> User {
+        User {
+            project: self.project,
+            wallet: self.wallet,
+            daily_logins: self.daily_logins,
+            quests: self.quests,
+            referrals: self.referrals,
+            total_tgem_earned: self.total_tgem_earned,
+            last_login: self.last_login,
+            bump: self.bump,
+            rent_payer: self.wallet,
+            pending_rewards: 0,
+            version: User::VERSION,
+            event_chain_hash: [0; 32],
+            event_seq: 0,
+            user_index: 0,
+            last_active_day: 0,
+            xp: 0,
+            tier: 0,
+            referral_bindings: 0,
+            has_activity: false,
+            reserved: [0; User::RESERVED_LEN],
+        }
+    }
+}
+
+/// Blank a departing project's registry slot, leaving later projects' indices
+/// in place
+fn unregister_project(
+    project: &Account<Project>,
+    page: Option<&mut ProjectRegistryPage>,
+) -> Result<()> {
+    if project.registry_index == Project::UNREGISTERED {
+        return Ok(());
+    }
+    let page = page.ok_or(ErrorCode::RegistryPageRequired)?;
+    let slot = (project.registry_index % ProjectRegistryPage::CAPACITY) as usize;
+    require!(
+        page.projects.get(slot) == Some(&project.key()),
+        ErrorCode::RegistryPageRequired
+    );
+    page.projects[slot] = Pubkey::default();
+    Ok(())
+}
+
+/// The tombstone a closed user left behind, if any
+fn read_closed_user(info: &AccountInfo) -> Result<Option<ClosedUser>> {
+    if info.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidUser);
+    Ok(Some(ClosedUser::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+}
+
+/// Accounts every single-user `record_*` instruction updates, borrowed from
+/// `RecordEvent` or `RecordCustomEvent`
+struct EventHooks<'a, 'info> {
+    project: &'a mut Account<'info, Project>,
+    user: &'a mut Account<'info, User>,
+    daily_stats: &'a mut Account<'info, DailyStats>,
+    daily_stats_bump: u8,
+    tier_config: Option<&'a TierConfig>,
+    season_stats: Option<&'a mut SeasonStats>,
+    season_stats_bump: Option<u8>,
+    activity: Option<&'a AccountLoader<'info, UserActivity>>,
+    leaderboards: &'info [AccountInfo<'info>],
+}
+
+impl<'info> RecordEvent<'info> {
+    fn hooks<'a>(
+        &'a mut self,
+        bumps: &RecordEventBumps,
+        leaderboards: &'info [AccountInfo<'info>],
+    ) -> EventHooks<'a, 'info> {
+        EventHooks {
+            project: &mut self.project,
+            user: &mut self.user,
+            daily_stats: &mut self.daily_stats,
+            daily_stats_bump: bumps.daily_stats,
+            tier_config: self.tier_config.as_deref(),
+            season_stats: self.season_stats.as_deref_mut(),
+            season_stats_bump: bumps.season_stats,
+            activity: self.activity.as_ref(),
+            leaderboards,
+        }
+    }
+}
+
+impl<'info> RecordCustomEvent<'info> {
+    fn hooks<'a>(
+        &'a mut self,
+        bumps: &RecordCustomEventBumps,
+        leaderboards: &'info [AccountInfo<'info>],
+    ) -> EventHooks<'a, 'info> {
+        EventHooks {
+            project: &mut self.project,
+            user: &mut self.user,
+            daily_stats: &mut self.daily_stats,
+            daily_stats_bump: bumps.daily_stats,
+            tier_config: self.tier_config.as_deref(),
+            season_stats: self.season_stats.as_deref_mut(),
+            season_stats_bump: bumps.season_stats,
+            activity: self.activity.as_ref(),
+            leaderboards,
+        }
+    }
+}
+
+/// Run an event the user's counters already reflect through daily stats, XP
+/// and tier, season stats, leaderboards, the activity log and the hash chain,
+/// then emit `EventRecorded`
+fn record_hooks(
+    hooks: EventHooks,
+    event_type: EventType,
+    reference: [u8; 32],
+    count: u64,
+    reward: u64,
+    timestamp: i64,
+) -> Result<()> {
+    let EventHooks {
+        project,
+        user,
+        daily_stats,
+        daily_stats_bump,
+        tier_config,
+        mut season_stats,
+        season_stats_bump,
+        activity,
+        leaderboards,
+    } = hooks;
+
+    daily_stats.open(project.key(), daily_stats_bump)?;
+    daily_stats.record_event(user, &event_type);
+    let xp = award_xp(project, tier_config, user, |config| config.event_xp(&event_type))?;
+    record_season(
+        project,
+        season_stats.as_deref_mut(),
+        season_stats_bump,
+        user,
+        &event_type,
+        xp,
+        timestamp,
+    )?;
+    update_leaderboards(leaderboards, project, user, season_stats.as_deref(), timestamp)?;
+    log_activity(
+        activity,
+        user,
+        ActivityEntry::new(&event_type, timestamp, reward, reference),
+    )?;
+
+    project.total_events = project.total_events.saturating_add(1);
+
+    let (event_seq, event_chain_hash) = user.chain_event(&event_type, &reference, count, timestamp);
+    emit!(EventRecorded {
+        user: user.wallet,
+        project: project.key(),
+        event_type,
+        payload_hash: reference,
+        count,
+        event_seq,
+        event_chain_hash,
+        timestamp,
+    });
+    Ok(())
+}
+
+/// Apply a built-in event to `user`, returning the user's new count for that type
+fn apply_event(user: &mut User, event_type: &EventType, current_time: i64) -> Result<u64> {
+    match event_type {
+        EventType::DailyLogin => {
+            // Check if already logged in today
+            require!(
+                current_time - user.last_login >= SECONDS_PER_DAY,
+                ErrorCode::AlreadyLoggedInToday
+            );
+            user.daily_logins = user.daily_logins.saturating_add(1);
+            user.last_login = current_time;
+            Ok(user.daily_logins)
+        }
+        EventType::Quest => {
+            user.quests = user.quests.saturating_add(1);
+            Ok(user.quests)
+        }
+        EventType::Referral => {
+            user.referrals = user.referrals.saturating_add(1);
+            Ok(user.referrals)
+        }
+        EventType::Custom(_) => err!(ErrorCode::CustomEventNeedsDefinition),
+    }
+}
+
+/// Deserialize a batch `User` and check it is `project`'s canonical, writable PDA
+fn load_batch_user<'info>(
+    info: &'info AccountInfo<'info>,
+    project: &Pubkey,
+) -> Result<Account<'info, User>> {
+    require!(info.is_writable, ErrorCode::InvalidUser);
+    let user = Account::<User>::try_from(info)?;
+    require!(user.project == *project, ErrorCode::InvalidProject);
+    require!(user.version == User::VERSION, ErrorCode::AccountNotMigrated);
+    let expected = Pubkey::create_program_address(
+        &[b"user", project.as_ref(), user.wallet.as_ref(), &[user.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| error!(ErrorCode::InvalidUser))?;
+    require_keys_eq!(expected, info.key(), ErrorCode::InvalidUser);
+    Ok(user)
+}
+
+/// Append to the user's history, which must be passed once they created one
+fn log_activity(
+    activity: Option<&AccountLoader<UserActivity>>,
+    user: &User,
+    entry: ActivityEntry,
+) -> Result<()> {
+    let Some(activity) = activity else {
+        // Users who never created a buffer simply go unlogged
+        require!(!user.has_activity, ErrorCode::ActivityAccountRequired);
+        return Ok(());
+    };
+    append_activity(activity, entry)
+}
+
+/// Load a batch user's activity buffer from its `[b"user_activity", user]`
+/// PDA, or `None` if the user never created one
+fn load_batch_activity<'info>(
+    info: &'info AccountInfo<'info>,
+    user: &Pubkey,
+) -> Result<Option<AccountLoader<'info, UserActivity>>> {
+    let (address, _) = Pubkey::find_program_address(&[b"user_activity", user.as_ref()], &crate::ID);
+    require_keys_eq!(info.key(), address, ErrorCode::InvalidActivityAccount);
+    if info.data_is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(AccountLoader::try_from(info)?))
+}
+
+/// Write `entry` at the ring buffer head, overwriting the oldest once full
+fn append_activity(activity: &AccountLoader<UserActivity>, entry: ActivityEntry) -> Result<()> {
+    let slot = {
+        let mut header = activity.load_mut()?;
+        if header.capacity == 0 {
+            return Ok(());
+        }
+        let slot = header.head;
+        header.head = (header.head + 1) % header.capacity;
+        header.len = header.len.saturating_add(1).min(header.capacity);
+        slot
+    };
+
+    let offset = UserActivity::ENTRIES_OFFSET + slot as usize * ActivityEntry::LEN;
+    let info = activity.to_account_info();
+    let mut data = info.try_borrow_mut_data()?;
+    data[offset..offset + ActivityEntry::LEN].copy_from_slice(bytemuck::bytes_of(&entry));
+    Ok(())
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchEvent {
+    pub event_type: EventType,
+    /// Index into `remaining_accounts`
+    pub user_index: u8,
+    pub payload_hash: [u8; 32],
+}
+
+/// Left behind by `close_user` at `[b"closed_user", project, wallet]` and
+/// read back when the wallet registers again
+#[account]
+pub struct ClosedUser {
+    pub user_index: u64,          // 8
+    pub last_login: i64,          // 8
+    pub last_active_day: i64,     // 8
+    pub event_seq: u64,           // 8
+    pub event_chain_hash: [u8; 32], // 32
+    /// Paid the tombstone's rent out of the first closed `User`'s
+    pub rent_payer: Pubkey,       // 32
+    pub bump: u8,                 // 1
+}
+
+impl ClosedUser {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 32 + 32 + 1;
+
+    pub fn from_user(user: &User, rent_payer: Pubkey, bump: u8) -> Self {
+        Self {
+            user_index: user.user_index,
+            last_login: user.last_login,
+            last_active_day: user.last_active_day,
+            event_seq: user.event_seq,
+            event_chain_hash: user.event_chain_hash,
+            rent_payer,
+            bump,
+        }
+    }
+}
+
+/// Ties a referred wallet to its referrer from `record_referral` until
+/// `release_referral`, at `[b"referral", project, referred]`
+#[account]
+pub struct ReferralBinding {
+    pub project: Pubkey,          // 32
+    /// The referrer's `User` account
+    pub referrer: Pubkey,         // 32
+    /// The referred wallet
+    pub referred: Pubkey,         // 32
+    pub rent_payer: Pubkey,       // 32
+    pub created_at: i64,          // 8
+    pub bump: u8,                 // 1
+}
+
+impl ReferralBinding {
+    pub const LEN: usize = 32 + 32 + 32 + 32 + 8 + 1;
+}
+
+/// Header of a user's recent-event ring buffer. `capacity` `ActivityEntry`
+/// records follow it in the account data; `head` is the next slot to write
+#[account(zero_copy)]
+pub struct UserActivity {
+    pub user: Pubkey,             // 32
+    /// Refunded when the buffer is closed
+    pub rent_payer: Pubkey,       // 32
+    pub capacity: u32,            // 4
+    pub head: u32,                // 4
+    pub len: u32,                 // 4
+    pub bump: u8,                 // 1
+    pub _padding: [u8; 3],        // 3
+}
+
+impl UserActivity {
+    pub const MAX_CAPACITY: u16 = 128;
+    pub const LEN: usize = 32 + 32 + 4 + 4 + 4 + 1 + 3;
+    pub const ENTRIES_OFFSET: usize = 8 + Self::LEN;
+
+    pub fn space(capacity: u16) -> usize {
+        Self::ENTRIES_OFFSET + capacity as usize * ActivityEntry::LEN
+    }
+}
+
+#[zero_copy]
+pub struct ActivityEntry {
+    pub timestamp: i64,           // 8
+    pub reward: u64,              // 8
+    /// Quest id hash, referred wallet or custom event payload hash
+    pub reference: [u8; 32],      // 32
+    pub custom_event_id: u16,     // 2
+    /// See `EventType::code`
+    pub kind: u8,                 // 1
+    pub _padding: [u8; 5],        // 5
+}
+
+impl ActivityEntry {
+    pub const LEN: usize = 8 + 8 + 32 + 2 + 1 + 5;
+
+    pub fn new(event_type: &EventType, timestamp: i64, reward: u64, reference: [u8; 32]) -> Self {
+        let (kind, custom_event_id) = event_type.code();
+        Self {
+            timestamp,
+            reward,
+            reference,
+            custom_event_id,
+            kind,
+            _padding: [0; 5],
+        }
+    }
+}
+
+/// Project XP weights and tier table. A user's tier is the number of
+/// `boundaries` their XP has reached, e.g. [1_000, 5_000] gives Bronze/Silver/Gold
+#[account]
+pub struct TierConfig {
+    pub project: Pubkey,              // 32
+    pub xp_weights: XpWeights,        // XpWeights::LEN
+    pub boundaries: Vec<u64>,         // 4 + 8 * (MAX_TIERS - 1)
+    /// Reward multiplier per tier, in basis points
+    pub multipliers_bps: Vec<u16>,    // 4 + 2 * MAX_TIERS
+    pub bump: u8,                     // 1
+}
+
+impl TierConfig {
+    pub const MAX_TIERS: usize = 8;
+    pub const MAX_MULTIPLIER_BPS: u16 = 50_000;
+    pub const LEN: usize =
+        32 + XpWeights::LEN + 4 + 8 * (Self::MAX_TIERS - 1) + 4 + 2 * Self::MAX_TIERS + 1;
+
+    pub fn event_xp(&self, event_type: &EventType) -> u64 {
+        match event_type {
+            EventType::DailyLogin => self.xp_weights.daily_login,
+            EventType::Quest => self.xp_weights.quest,
+            EventType::Referral => self.xp_weights.referral,
+            EventType::Custom(_) => self.xp_weights.custom_event,
+        }
+    }
+
+    pub fn tgem_xp(&self, amount: u64) -> u64 {
+        ((amount as u128) * (self.xp_weights.tgem_bps as u128) / 10_000) as u64
+    }
+
+    pub fn tier_for(&self, xp: u64) -> u8 {
+        self.boundaries.iter().take_while(|boundary| xp >= **boundary).count() as u8
+    }
+
+    pub fn scale_reward(&self, tier: u8, reward: u64) -> u64 {
+        let bps = self
+            .multipliers_bps
+            .get(tier as usize)
+            .copied()
+            .unwrap_or(10_000);
+        ((reward as u128) * (bps as u128) / 10_000) as u64
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct XpWeights {
+    pub daily_login: u64,             // 8
+    pub quest: u64,                   // 8
+    pub referral: u64,                // 8
+    pub custom_event: u64,            // 8
+    /// XP per TGEM earned, in basis points
+    pub tgem_bps: u32,                // 4
+}
+
+impl XpWeights {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 4;
+}
+
+/// Add XP to `user` and re-derive their tier, emitting `TierChanged` when it moves.
+/// Returns the XP awarded
+fn award_xp(
+    project: &Project,
+    tier_config: Option<&TierConfig>,
+    user: &mut User,
+    xp_for: impl FnOnce(&TierConfig) -> u64,
+) -> Result<u64> {
+    let Some(config) = tier_config else {
+        require!(!project.tiers_enabled, ErrorCode::TierConfigRequired);
+        return Ok(0);
+    };
+    let xp = xp_for(config);
+    user.xp = user.xp.saturating_add(xp);
+    let new_tier = config.tier_for(user.xp);
+    if new_tier != user.tier {
+        emit!(TierChanged {
+            user: user.wallet,
+            project: user.project,
+            old_tier: user.tier,
+            new_tier,
+            xp: user.xp,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        user.tier = new_tier;
+    }
+    Ok(xp)
+}
+
+/// A project season. `closed_at` is 0 while open; once set, the season's
+/// `SeasonStats` no longer change
+#[account]
+pub struct Season {
+    pub project: Pubkey,          // 32
+    pub number: u32,              // 4
+    pub start: i64,               // 8
+    pub end: i64,                 // 8
+    pub closed_at: i64,           // 8
+    pub bump: u8,                 // 1
+}
+
+impl Season {
+    pub const LEN: usize = 32 + 4 + 8 + 8 + 8 + 1;
+}
+
+/// A user's activity within one season; lifetime counters stay on `User`
+#[account]
+pub struct SeasonStats {
+    pub project: Pubkey,          // 32
+    pub season: u32,              // 4
+    pub user: Pubkey,             // 32
+    pub daily_logins: u64,        // 8
+    pub quests: u64,              // 8
+    pub referrals: u64,           // 8
+    pub custom_events: u64,       // 8
+    pub xp: u64,                  // 8
+    pub bump: u8,                 // 1
+}
+
+impl SeasonStats {
+    pub const LEN: usize = 32 + 4 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    pub fn new(project: Pubkey, season: u32, user: Pubkey, bump: u8) -> Self {
+        Self {
+            project,
+            season,
+            user,
+            daily_logins: 0,
+            quests: 0,
+            referrals: 0,
+            custom_events: 0,
+            xp: 0,
+            bump,
+        }
+    }
+
+    pub fn record_event(&mut self, event_type: &EventType, xp: u64) {
+        let counter = match event_type {
+            EventType::DailyLogin => &mut self.daily_logins,
+            EventType::Quest => &mut self.quests,
+            EventType::Referral => &mut self.referrals,
+            EventType::Custom(_) => &mut self.custom_events,
+        };
+        *counter = counter.saturating_add(1);
+        self.xp = self.xp.saturating_add(xp);
+    }
+}
+
+/// Attribute an event to the live season, if any. `stats` comes from
+/// `init_if_needed`, so a fresh account gets its key fields here
+fn record_season(
+    project: &Account<Project>,
+    stats: Option<&mut SeasonStats>,
+    bump: Option<u8>,
+    user: &Account<User>,
+    event_type: &EventType,
+    xp: u64,
+    timestamp: i64,
+) -> Result<()> {
+    let Some(season) = project.live_season(timestamp) else {
+        return Ok(());
+    };
+    let (stats, bump) = stats.zip(bump).ok_or(ErrorCode::SeasonStatsRequired)?;
+    if stats.user == Pubkey::default() {
+        *stats = SeasonStats::new(project.key(), season, user.key(), bump);
+    }
+    stats.record_event(event_type, xp);
+    Ok(())
+}
+
+/// Load a batch user's `SeasonStats`, creating the PDA with `payer` funds if needed
+fn load_batch_season_stats<'info>(
+    info: &'info AccountInfo<'info>,
+    project: &Pubkey,
+    season: u32,
+    user: &Pubkey,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<Account<'info, SeasonStats>> {
+    let season_le = season.to_le_bytes();
+    let (expected, bump) = Pubkey::find_program_address(
+        &[b"season_stats", project.as_ref(), &season_le, user.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(expected, info.key(), ErrorCode::InvalidSeasonStats);
+
+    if info.data_is_empty() {
+        let space = 8 + SeasonStats::LEN;
+        let shortfall = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(info.lamports());
+        if shortfall > 0 {
+            let cpi_ctx = CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: info.clone(),
+                },
+            );
+            system_program::transfer(cpi_ctx, shortfall)?;
+        }
+        let seeds: &[&[u8]] = &[b"season_stats", project.as_ref(), &season_le, user.as_ref(), &[bump]];
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::Allocate { account_to_allocate: info.clone() },
+                &[seeds],
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::Assign { account_to_assign: info.clone() },
+                &[seeds],
+            ),
+            &crate::ID,
+        )?;
+        SeasonStats::new(*project, season, *user, bump)
+            .try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    }
+    Account::try_from(info)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeaderboardMetric {
+    Xp,
+    Quests,
+    Referrals,
+    TgemEarned,
+}
+
+impl LeaderboardMetric {
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Self::Xp),
+            1 => Some(Self::Quests),
+            2 => Some(Self::Referrals),
+            3 => Some(Self::TgemEarned),
+            _ => None,
+        }
+    }
+
+    pub fn user_score(&self, user: &User) -> u64 {
+        match self {
+            Self::Xp => user.xp,
+            Self::Quests => user.quests,
+            Self::Referrals => user.referrals,
+            Self::TgemEarned => user.total_tgem_earned,
+        }
+    }
+
+    /// TGEM earned isn't tracked per season
+    pub fn season_score(&self, stats: &SeasonStats) -> Option<u64> {
+        match self {
+            Self::Xp => Some(stats.xp),
+            Self::Quests => Some(stats.quests),
+            Self::Referrals => Some(stats.referrals),
+            Self::TgemEarned => None,
+        }
+    }
+}
+
+/// Header of a project's top-N table for one `LeaderboardMetric`. `len`
+/// `LeaderboardEntry` records follow it, sorted by descending score. Entries
+/// only move when a user's score is pushed, so a board converges once every
+/// user has recorded an event with it or had `submit_leaderboard_score` called
+#[account(zero_copy)]
+pub struct Leaderboard {
+    pub project: Pubkey,          // 32
+    /// 0 = lifetime scores
+    pub season: u32,              // 4
+    pub capacity: u32,            // 4
+    pub len: u32,                 // 4
+    /// `LeaderboardMetric` as u8
+    pub metric: u8,               // 1
+    pub bump: u8,                 // 1
+    pub _padding: [u8; 2],        // 2
+}
+
+impl Leaderboard {
+    pub const MAX_CAPACITY: u32 = 100;
+    pub const LEN: usize = 32 + 4 + 4 + 4 + 1 + 1 + 2;
+    pub const ENTRIES_OFFSET: usize = 8 + Self::LEN;
+
+    pub fn space(capacity: u32) -> usize {
+        Self::ENTRIES_OFFSET + capacity as usize * LeaderboardEntry::LEN
+    }
+
+    /// Move `wallet` to its rank for `score`, dropping the lowest entry when
+    /// full. Ties keep the earlier holder ahead. Returns whether `entries` changed
+    pub fn place(
+        entries: &mut Vec<LeaderboardEntry>,
+        capacity: usize,
+        wallet: Pubkey,
+        score: u64,
+    ) -> bool {
+        if let Some(index) = entries.iter().position(|entry| entry.wallet == wallet) {
+            if entries[index].score == score {
+                return false;
+            }
+            entries.remove(index);
+        }
+        let rank = entries.partition_point(|entry| entry.score >= score);
+        if rank >= capacity {
+            return false;
+        }
+        entries.insert(rank, LeaderboardEntry { wallet, score });
+        entries.truncate(capacity);
+        true
+    }
+
+    /// Drop `wallet`'s entry, if any. Returns whether `entries` changed
+    pub fn evict(entries: &mut Vec<LeaderboardEntry>, wallet: Pubkey) -> bool {
+        let len = entries.len();
+        entries.retain(|entry| entry.wallet != wallet);
+        entries.len() != len
+    }
+}
+
+#[zero_copy]
+pub struct LeaderboardEntry {
+    pub wallet: Pubkey,           // 32
+    pub score: u64,               // 8
+}
+
+impl LeaderboardEntry {
+    pub const LEN: usize = 32 + 8;
+}
+
+fn is_leaderboard(info: &AccountInfo) -> bool {
+    info.owner == &crate::ID
+        && info
+            .try_borrow_data()
+            .is_ok_and(|data| data.starts_with(&Leaderboard::DISCRIMINATOR))
+}
+
+/// The user's score on `leaderboard`; season boards need the matching `SeasonStats`
+fn leaderboard_score(
+    leaderboard: &Leaderboard,
+    user: &User,
+    season_stats: Option<&SeasonStats>,
+) -> Result<u64> {
+    let metric = LeaderboardMetric::from_code(leaderboard.metric)
+        .ok_or(ErrorCode::InvalidLeaderboard)?;
+    if leaderboard.season == 0 {
+        return Ok(metric.user_score(user));
+    }
+    let stats = season_stats
+        .filter(|stats| stats.season == leaderboard.season)
+        .ok_or(ErrorCode::SeasonStatsRequired)?;
+    metric
+        .season_score(stats)
+        .ok_or_else(|| error!(ErrorCode::UnsupportedLeaderboardMetric))
+}
+
+/// Load a leaderboard `project` may currently change: a lifetime board, or
+/// one for the live season
+fn writable_leaderboard<'info>(
+    info: &'info AccountInfo<'info>,
+    project: &Account<Project>,
+    timestamp: i64,
+) -> Result<AccountLoader<'info, Leaderboard>> {
+    let leaderboard = AccountLoader::<Leaderboard>::try_from(info)?;
+    {
+        let header = leaderboard.load()?;
+        require_keys_eq!(header.project, project.key(), ErrorCode::InvalidLeaderboard);
+        require!(
+            header.season == 0 || Some(header.season) == project.live_season(timestamp),
+            ErrorCode::InvalidLeaderboard
+        );
+    }
+    Ok(leaderboard)
+}
+
+/// Push the user's scores to each passed leaderboard. Season boards only
+/// accept updates while their season is live
+fn update_leaderboards<'info>(
+    leaderboards: &'info [AccountInfo<'info>],
+    project: &Account<Project>,
+    user: &User,
+    season_stats: Option<&SeasonStats>,
+    timestamp: i64,
+) -> Result<()> {
+    for info in leaderboards {
+        let leaderboard = writable_leaderboard(info, project, timestamp)?;
+        let score = leaderboard_score(&*leaderboard.load()?, user, season_stats)?;
+        submit_score(&leaderboard, user.wallet, score)?;
+    }
+    Ok(())
+}
+
+/// Remove a departing wallet from each passed leaderboard
+fn evict_from_leaderboards<'info>(
+    leaderboards: &'info [AccountInfo<'info>],
+    project: &Account<Project>,
+    wallet: Pubkey,
+    timestamp: i64,
+) -> Result<()> {
+    for info in leaderboards {
+        evict_entry(&writable_leaderboard(info, project, timestamp)?, wallet)?;
+    }
+    Ok(())
+}
+
+fn evict_entry(leaderboard: &AccountLoader<Leaderboard>, wallet: Pubkey) -> Result<()> {
+    let (_, mut entries) = read_entries(leaderboard)?;
+    if Leaderboard::evict(&mut entries, wallet) {
+        write_entries(leaderboard, &entries)?;
+    }
+    Ok(())
+}
+
+/// Re-rank `wallet` on the leaderboard, rewriting only when its standing changes
+fn submit_score(leaderboard: &AccountLoader<Leaderboard>, wallet: Pubkey, score: u64) -> Result<()> {
+    let (capacity, mut entries) = read_entries(leaderboard)?;
+    if Leaderboard::place(&mut entries, capacity, wallet, score) {
+        write_entries(leaderboard, &entries)?;
+    }
+    Ok(())
+}
+
+/// The board's capacity and its current entries
+fn read_entries(leaderboard: &AccountLoader<Leaderboard>) -> Result<(usize, Vec<LeaderboardEntry>)> {
+    let (capacity, len) = {
+        let header = leaderboard.load()?;
+        (header.capacity as usize, header.len as usize)
+    };
+    let info = leaderboard.to_account_info();
+    let data = info.try_borrow_data()?;
+    let entries = data[Leaderboard::ENTRIES_OFFSET..]
+        .chunks_exact(LeaderboardEntry::LEN)
+        .take(len)
+        .map(bytemuck::pod_read_unaligned)
+        .collect();
+    Ok((capacity, entries))
+}
+
+fn write_entries(leaderboard: &AccountLoader<Leaderboard>, entries: &[LeaderboardEntry]) -> Result<()> {
+    {
+        let info = leaderboard.to_account_info();
+        let mut data = info.try_borrow_mut_data()?;
+        data[Leaderboard::ENTRIES_OFFSET..]
+            .chunks_exact_mut(LeaderboardEntry::LEN)
+            .zip(entries)
+            .for_each(|(slot, entry)| slot.copy_from_slice(bytemuck::bytes_of(entry)));
+    }
+    leaderboard.load_mut()?.len = entries.len() as u32;
+    Ok(())
+}
+
+/// Per-project activity for one UTC day, opened by the day's first registration or event
+#[account]
+pub struct DailyStats {
+    pub project: Pubkey,          // 32
+    pub day: i64,                 // 8 (unix_timestamp / SECONDS_PER_DAY)
+    pub active_users: u64,        // 8 (distinct users with an event)
+    pub new_users: u64,           // 8
+    pub daily_logins: u64,        // 8
+    pub quests: u64,              // 8
+    pub referrals: u64,           // 8
+    pub custom_events: u64,       // 8
+    pub bump: u8,                 // 1
+}
+
+impl DailyStats {
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    pub fn today() -> Result<i64> {
+        Ok(Clock::get()?.unix_timestamp / SECONDS_PER_DAY)
+    }
+
+    /// Fill in the key fields of an account just created by `init_if_needed`
+    pub fn open(&mut self, project: Pubkey, bump: u8) -> Result<()> {
+        if self.project == Pubkey::default() {
+            self.project = project;
+            self.day = Self::today()?;
+            self.bump = bump;
+        }
+        Ok(())
+    }
+
+    /// Count `event_type`, and `user` as active if this is their first event today
+    pub fn record_event(&mut self, user: &mut User, event_type: &EventType) {
+        if user.last_active_day != self.day {
+            user.last_active_day = self.day;
+            self.active_users = self.active_users.saturating_add(1);
+        }
+        let counter = match event_type {
+            EventType::DailyLogin => &mut self.daily_logins,
+            EventType::Quest => &mut self.quests,
+            EventType::Referral => &mut self.referrals,
+            EventType::Custom(_) => &mut self.custom_events,
+        };
+        *counter = counter.saturating_add(1);
+    }
+}
+
+/// Project-defined event type, recorded with `record_custom_event`
+#[account]
+pub struct EventDefinition {
+    pub project: Pubkey,          // 32
+    pub event_id: u16,            // 2
+    pub name: String,             // 4 + 32 = 36
+    pub reward: u64,              // 8 (credited to pending_rewards)
+    pub cooldown: i64,            // 8 (seconds between records, per user)
+    pub cap: u64,                 // 8 (records per user, 0 = unlimited)
+    pub required_signer: RequiredSigner, // 1
+    pub active: bool,             // 1
+    pub total_recorded: u64,      // 8
+    pub bump: u8,                 // 1
+}
+
+impl EventDefinition {
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const LEN: usize = 32 + 2 + 36 + 8 + 8 + 8 + 1 + 1 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RequiredSigner {
+    User,
+    Authority,
+    Both,
+}
+
+impl RequiredSigner {
+    pub fn satisfied(self, user_signed: bool, authority_signed: bool) -> bool {
+        match self {
+            Self::User => user_signed,
+            Self::Authority => authority_signed,
+            Self::Both => user_signed && authority_signed,
+        }
+    }
+}
+
+/// Per-user counters for every custom event type the user has recorded
+#[account]
+pub struct UserEventCounters {
+    pub user: Pubkey,                 // 32
+    pub entries: Vec<EventCounter>,   // 4 + EventCounter::LEN * MAX_ENTRIES
+    pub bump: u8,                     // 1
+}
+
+impl UserEventCounters {
+    pub const MAX_ENTRIES: usize = 16;
+    pub const LEN: usize = 32 + 4 + EventCounter::LEN * Self::MAX_ENTRIES + 1;
+
+    /// Counter for `event_id`, added on first use
+    pub fn entry(&mut self, event_id: u16) -> Result<&mut EventCounter> {
+        let index = match self.entries.iter().position(|e| e.event_id == event_id) {
+            Some(index) => index,
+            None => {
+                require!(
+                    self.entries.len() < Self::MAX_ENTRIES,
+                    ErrorCode::TooManyEventTypes
+                );
+                self.entries.push(EventCounter {
+                    event_id,
+                    count: 0,
+                    last_recorded: 0,
+                });
+                self.entries.len() - 1
+            }
+        };
+        Ok(&mut self.entries[index])
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EventCounter {
+    pub event_id: u16,                // 2
+    pub count: u64,                   // 8
+    pub last_recorded: i64,           // 8
+}
+
+impl EventCounter {
+    pub const LEN: usize = 2 + 8 + 8;
+
+    /// Count an occurrence at `now` if `definition`'s cooldown and cap allow
+    /// it, returning the new count
+    pub fn record(&mut self, definition: &EventDefinition, now: i64) -> Result<u64> {
+        require!(
+            self.count == 0 || now - self.last_recorded >= definition.cooldown,
+            ErrorCode::EventOnCooldown
+        );
+        require!(
+            definition.cap == 0 || self.count < definition.cap,
+            ErrorCode::EventCapReached
+        );
+        self.count = self.count.saturating_add(1);
+        self.last_recorded = now;
+        Ok(self.count)
+    }
+}
+
+/// Create the PDA `account` with rent paid from the program-owned `funder`.
+/// The funder's lamports move directly, then the PDA allocates and assigns itself
+fn create_funded_pda<'info>(
+    funder: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let cost = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+    **funder.try_borrow_mut_lamports()? -= cost;
+    **account.try_borrow_mut_lamports()? += cost;
+
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate { account_to_allocate: account.clone() },
+            &[seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Assign { account_to_assign: account.clone() },
+            &[seeds],
+        ),
+        &crate::ID,
+    )?;
+    Ok(())
+}
+
+/// Project-funded lamport pool paying `User` rent for sponsored registrations
+#[account]
+pub struct RentSponsor {
+    pub project: Pubkey,          // 32
+    pub daily_budget: u64,        // 8 (lamports)
+    pub day_index: i64,           // 8
+    pub spent_today: u64,         // 8
+    pub sponsored_count: u64,     // 8
+    pub bump: u8,                 // 1
+}
+
+impl RentSponsor {
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct AdminCouncil {
+    pub create_key: Pubkey,           // 32
+    pub members: Vec<Pubkey>,         // 4 + 32 * council::MAX_MEMBERS
+    pub threshold: u8,                // 1
+    pub proposal_count: u64,          // 8
+    pub bump: u8,                     // 1
+}
+
+impl AdminCouncil {
+    pub const LEN: usize = 32 + 4 + 32 * council::MAX_MEMBERS + 1 + 8 + 1;
+}
+
+/// Closed to the proposer on execution or cancellation; the index-derived
+/// address is never reused, so an executed proposal can't be replayed
+#[account]
+pub struct CouncilProposal {
+    pub council: Pubkey,                   // 32
+    pub index: u64,                        // 8
+    pub proposer: Pubkey,                  // 32
+    pub instruction_data: Vec<u8>,         // 4 + MAX_DATA_LEN
+    pub accounts: Vec<CouncilAccountMeta>, // 4 + 34 * MAX_ACCOUNTS
+    pub approvals: Vec<Pubkey>,            // 4 + 32 * MAX_MEMBERS
+    pub bump: u8,                          // 1
+}
+
+impl CouncilProposal {
+    pub const LEN: usize = 32 + 8 + 32
+        + 4 + council::MAX_DATA_LEN
+        + 4 + CouncilAccountMeta::LEN * council::MAX_ACCOUNTS
+        + 4 + 32 * council::MAX_MEMBERS
+        + 1;
+}
+
+#[event]
+pub struct CouncilUpdated {
+    pub council: Pubkey,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouncilProposalCreated {
+    pub council: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouncilProposalApproved {
+    pub council: Pubkey,
+    pub proposal: Pubkey,
+    pub member: Pubkey,
+    pub approvals: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouncilProposalExecuted {
+    pub council: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouncilProposalCancelled {
+    pub council: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProjectCreated {
+    pub project: Pubkey,
+    pub authority: Pubkey,
+    pub project_key: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserRegistered {
+    pub user: Pubkey,
+    pub project: Pubkey,
+    pub sponsored: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EventRecorded {
+    pub user: Pubkey,
+    pub project: Pubkey,
+    pub event_type: EventType,
+    /// Quest id hash, referred wallet, or caller-supplied evidence; zero for logins
+    pub payload_hash: [u8; 32],
+    pub count: u64,
+    /// Position of this event in the user's history, starting at 1
+    pub event_seq: u64,
+    /// `User::event_chain_hash` after this event
+    pub event_chain_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// A `record_events_batch` entry the user wasn't eligible for
+#[event]
+pub struct BatchEventSkipped {
+    pub user: Pubkey,
+    pub project: Pubkey,
+    /// Index of the entry in the batch
+    pub position: u32,
+    pub event_type: EventType,
+    /// Error the entry would have failed with, e.g. `AlreadyLoggedInToday`
+    pub error_code: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolConfigUpdated {
+    pub old_creation_policy: CreationPolicy,
+    pub new_creation_policy: CreationPolicy,
+    pub old_treasury: Pubkey,
+    pub new_treasury: Pubkey,
+    pub old_creation_fee: u64,
+    pub new_creation_fee: u64,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProjectCreatorAllowed {
+    pub creator: Pubkey,
+    pub allowed: bool,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProjectMetadataUpdated {
+    pub project: Pubkey,
+    pub display_name: String,
+    pub uri: String,
+    pub category: ProjectCategory,
+    pub logo_hash: [u8; 32],
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProjectVerified {
+    pub project: Pubkey,
+    pub old_verified: bool,
+    pub new_verified: bool,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProjectAliasUpdated {
+    pub project: Pubkey,
+    pub alias: String,
+    pub added: bool,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProjectSunsetStarted {
+    pub project: Pubkey,
+    pub total_users: u64,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProjectClosed {
+    pub project: Pubkey,
+    pub project_key: String,
+    pub actor: Pubkey,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-#[instruction(project_key: String)]
-pub struct InitializeProject<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + Project::LEN,
-        seeds = [b"project", project_key.as_bytes()],
-        bump
-    )]
-    pub project: Account<'info, Project>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct SponsorBudgetUpdated {
+    pub project: Pubkey,
+    pub old_daily_budget: u64,
+    pub new_daily_budget: u64,
+    pub actor: Pubkey,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct RegisterUser<'info> {
-    #[account(mut)]
-    pub project: Account<'info, Project>,
-    
-    #[account(
-        init,
-        payer = user_wallet,
-        space = 8 + User::LEN,
-        seeds = [b"user", project.key().as_ref(), user_wallet.key().as_ref()],
-        bump
-    )]
-    pub user: Account<'info, User>,
-    
-    #[account(mut)]
-    pub user_wallet: Signer<'info>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub version: u8,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct RecordEvent<'info> {
-    #[account(mut)]
-    pub project: Account<'info, Project>,
-    
-    #[account(mut)]
-    pub user: Account<'info, User>,
-    
-    pub user_wallet: Signer<'info>,
+#[event]
+pub struct EventDefinitionUpdated {
+    pub project: Pubkey,
+    pub event_id: u16,
+    pub reward: u64,
+    pub cooldown: i64,
+    pub cap: u64,
+    pub required_signer: RequiredSigner,
+    pub active: bool,
+    pub actor: Pubkey,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct UpdateTGEM<'info> {
-    pub project: Account<'info, Project>,
-    
-    #[account(
-        mut,
-        constraint = authority.key() == project.authority @ ErrorCode::Unauthorized
-    )]
-    pub user: Account<'info, User>,
-    
-    /// CHECK: User wallet for reference
-    pub user_wallet: AccountInfo<'info>,
-    
-    #[account(constraint = authority.key() == project.authority @ ErrorCode::Unauthorized)]
-    pub authority: Signer<'info>,
+#[event]
+pub struct TierConfigUpdated {
+    pub project: Pubkey,
+    pub xp_weights: XpWeights,
+    pub boundaries: Vec<u64>,
+    pub multipliers_bps: Vec<u16>,
+    pub actor: Pubkey,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct UpdateProject<'info> {
-    #[account(
-        mut,
-        constraint = authority.key() == project.authority @ ErrorCode::Unauthorized
-    )]
-    pub project: Account<'info, Project>,
-    
-    pub authority: Signer<'info>,
+#[event]
+pub struct TiersDisabled {
+    pub project: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
 }
 
-#[account]
-pub struct Project {
-    pub authority: Pubkey,        // 32
-    pub project_key: String,      // 4 + 32 = 36
-    pub tgem_plus_enabled: bool,  // 1
-    pub total_users: u64,         // 8
-    pub total_events: u64,        // 8
-    pub bump: u8,                 // 1
+#[event]
+pub struct TierChanged {
+    pub user: Pubkey,
+    pub project: Pubkey,
+    pub old_tier: u8,
+    pub new_tier: u8,
+    pub xp: u64,
+    pub timestamp: i64,
 }
 
-impl Project {
-    pub const LEN: usize = 32 + 36 + 1 + 8 + 8 + 1;
+#[event]
+pub struct SeasonStarted {
+    pub project: Pubkey,
+    pub number: u32,
+    pub start: i64,
+    pub end: i64,
+    pub actor: Pubkey,
+    pub timestamp: i64,
 }
 
-#[account]
-pub struct User {
-    pub project: Pubkey,          // 32
-    pub wallet: Pubkey,           // 32
-    pub daily_logins: u64,        // 8
-    pub quests: u64,              // 8
-    pub referrals: u64,           // 8
-    pub total_tgem_earned: u64,   // 8
-    pub last_login: i64,          // 8
-    pub bump: u8,                 // 1
+#[event]
+pub struct SeasonClosed {
+    pub project: Pubkey,
+    pub number: u32,
+    pub actor: Pubkey,
+    pub timestamp: i64,
 }
 
-impl User {
-    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+#[event]
+pub struct LeaderboardCreated {
+    pub project: Pubkey,
+    pub leaderboard: Pubkey,
+    pub metric: LeaderboardMetric,
+    pub season: u32,
+    pub capacity: u32,
+    pub actor: Pubkey,
+    pub timestamp: i64,
 }
 
 #[event]
-pub struct ProjectCreated {
+pub struct UserDeregistered {
+    pub user: Pubkey,
     pub project: Pubkey,
-    pub authority: Pubkey,
-    pub project_key: String,
+    pub rent_payer: Pubkey,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct UserRegistered {
+pub struct RewardsCredited {
+    pub project: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub old_pending: u64,
+    pub new_pending: u64,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserAccountClosed {
     pub user: Pubkey,
     pub project: Pubkey,
+    pub rent_payer: Pubkey,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct EventRecorded {
+pub struct ProjectReassigned {
+    pub project: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProjectReclaimed {
+    pub project: Pubkey,
+    pub project_key: String,
+    pub old_authority: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProjectConfigUpdated {
+    pub project: Pubkey,
+    pub old_tgem_plus_enabled: bool,
+    pub new_tgem_plus_enabled: bool,
+    pub old_restrict_user_close: bool,
+    pub new_restrict_user_close: bool,
+    pub old_activity_capacity: u16,
+    pub new_activity_capacity: u16,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TgemEarnedUpdated {
+    pub project: Pubkey,
     pub user: Pubkey,
+    pub amount: u64,
+    pub old_total: u64,
+    pub new_total: u64,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProjectAuthorityTransferProposed {
     pub project: Pubkey,
-    pub event_type: EventType,
-    pub count: u64,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProjectAuthorityTransferred {
+    pub project: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub actor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProjectAuthorityTransferCancelled {
+    pub project: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub actor: Pubkey,
     pub timestamp: i64,
 }
 
@@ -324,6 +4787,20 @@ pub enum EventType {
     DailyLogin,
     Quest,
     Referral,
+    Custom(u16),
+}
+
+impl EventType {
+    /// Compact (kind, custom event id) form: 0 = daily login, 1 = quest,
+    /// 2 = referral, 3 = custom
+    pub fn code(&self) -> (u8, u16) {
+        match self {
+            EventType::DailyLogin => (0, 0),
+            EventType::Quest => (1, 0),
+            EventType::Referral => (2, 0),
+            EventType::Custom(id) => (3, *id),
+        }
+    }
 }
 
 #[error_code]
@@ -345,4 +4822,308 @@ pub enum ErrorCode {
     
     #[msg("Already logged in today")]
     AlreadyLoggedInToday,
+    
+    #[msg("No pending authority transfer")]
+    NoPendingAuthority,
+    
+    #[msg("Project creation requires an allowlist entry")]
+    CreatorNotAllowed,
+    
+    #[msg("Treasury does not match protocol config")]
+    InvalidTreasury,
+    
+    #[msg("Project still has registered users")]
+    ProjectHasUsers,
+    
+    #[msg("Display name too long (max 32 chars)")]
+    DisplayNameTooLong,
+    
+    #[msg("Metadata URI too long (max 200 chars)")]
+    UriTooLong,
+    
+    #[msg("Project key must be uppercase A-Z, 0-9, '_' or '-'")]
+    InvalidProjectKey,
+    
+    #[msg("Project key is already in use")]
+    ProjectKeyTaken,
+    
+    #[msg("Project has too many aliases")]
+    TooManyAliases,
+    
+    #[msg("Project is closing")]
+    ProjectClosing,
+    
+    #[msg("Project is not closing")]
+    ProjectNotClosing,
+    
+    #[msg("Project still has aliases")]
+    ProjectHasAliases,
+    
+    #[msg("User has unclaimed rewards")]
+    UnclaimedRewards,
+    
+    #[msg("User has active referrals")]
+    ActiveReferrals,
+    
+    #[msg("Rent sponsor daily budget exceeded")]
+    SponsorBudgetExceeded,
+    
+    #[msg("Rent sponsor has insufficient lamports")]
+    SponsorUnderfunded,
+    
+    #[msg("User already registered")]
+    UserAlreadyRegistered,
+    
+    #[msg("Account must be migrated to the current version first")]
+    AccountNotMigrated,
+    
+    #[msg("Account is already at the current version")]
+    AlreadyMigrated,
+    
+    #[msg("Event name too long (max 32 chars)")]
+    EventNameTooLong,
+    
+    #[msg("Cooldown cannot be negative")]
+    InvalidCooldown,
+    
+    #[msg("Event definition is inactive")]
+    EventDefinitionInactive,
+    
+    #[msg("Event requires a signer that is missing")]
+    MissingRequiredSigner,
+    
+    #[msg("Event is on cooldown for this user")]
+    EventOnCooldown,
+    
+    #[msg("User reached the cap for this event")]
+    EventCapReached,
+    
+    #[msg("User has recorded too many distinct custom events")]
+    TooManyEventTypes,
+    
+    #[msg("Custom events must be recorded with record_custom_event")]
+    CustomEventNeedsDefinition,
+    
+    #[msg("Batch contains no events")]
+    EmptyBatch,
+    
+    #[msg("Batch user index out of range")]
+    InvalidUserIndex,
+    
+    #[msg("Batch lists the same user account twice")]
+    DuplicateBatchUser,
+    
+    #[msg("Project does not keep activity history")]
+    ActivityDisabled,
+    
+    #[msg("Activity capacity too large (max 128)")]
+    ActivityCapacityTooLarge,
+    
+    #[msg("Activity account does not belong to the user")]
+    InvalidActivityAccount,
+    
+    #[msg("Tier boundaries must ascend, with one multiplier (max 5x) per tier")]
+    InvalidTierConfig,
+    
+    #[msg("Tier config account required for this project")]
+    TierConfigRequired,
+    
+    #[msg("Season must end after it starts")]
+    InvalidSeasonWindow,
+    
+    #[msg("Close the current season first")]
+    SeasonOpen,
+    
+    #[msg("Project has no open season")]
+    NoOpenSeason,
+    
+    #[msg("Season stats account required while a season is live")]
+    SeasonStatsRequired,
+    
+    #[msg("Season stats account does not match the user and season")]
+    InvalidSeasonStats,
+    
+    #[msg("Batch accounts must be grouped per user")]
+    InvalidBatchAccounts,
+    
+    #[msg("Leaderboard does not match the project or live season")]
+    InvalidLeaderboard,
+    
+    #[msg("Leaderboard capacity must be between 1 and 100")]
+    InvalidLeaderboardCapacity,
+    
+    #[msg("Metric is not tracked per season")]
+    UnsupportedLeaderboardMetric,
+    
+    #[msg("Account data does not match a known legacy layout")]
+    UnknownLayout,
+    
+    #[msg("Daily stats can only be closed once their day is over")]
+    DayNotOver,
+    
+    #[msg("Wallet was already sponsored once; register it with register_user")]
+    AlreadySponsored,
+    
+    #[msg("Project's registry page required")]
+    RegistryPageRequired,
+    
+    #[msg("Project still has ClosedUser tombstones")]
+    ProjectHasClosedUsers,
+    
+    #[msg("User activity account required")]
+    ActivityAccountRequired,
+    
+    #[msg("Season stats can only be created or closed while a season is live")]
+    SeasonNotLive,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(cooldown: i64, cap: u64) -> EventDefinition {
+        EventDefinition {
+            project: Pubkey::new_unique(),
+            event_id: 1,
+            name: "quiz".to_string(),
+            reward: 100,
+            cooldown,
+            cap,
+            required_signer: RequiredSigner::User,
+            active: true,
+            total_recorded: 0,
+            bump: 255,
+        }
+    }
+
+    fn new_counter() -> EventCounter {
+        EventCounter {
+            event_id: 1,
+            count: 0,
+            last_recorded: 0,
+        }
+    }
+
+    fn error_code(result: Result<u64>) -> u32 {
+        match result {
+            Err(Error::AnchorError(err)) => err.error_code_number,
+            other => panic!("expected an anchor error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn cooldown_blocks_until_it_elapses() {
+        let definition = definition(3_600, 0);
+        let mut counter = new_counter();
+        assert_eq!(counter.record(&definition, 1_000).unwrap(), 1);
+        assert_eq!(
+            error_code(counter.record(&definition, 4_599)),
+            u32::from(ErrorCode::EventOnCooldown)
+        );
+        assert_eq!(counter.record(&definition, 4_600).unwrap(), 2);
+        assert_eq!(counter.last_recorded, 4_600);
+    }
+
+    #[test]
+    fn cap_stops_counting_and_zero_means_uncapped() {
+        let capped = definition(0, 2);
+        let mut counter = new_counter();
+        counter.record(&capped, 1).unwrap();
+        counter.record(&capped, 2).unwrap();
+        assert_eq!(
+            error_code(counter.record(&capped, 3)),
+            u32::from(ErrorCode::EventCapReached)
+        );
+        assert_eq!(counter.count, 2);
+
+        let uncapped = definition(0, 0);
+        let mut counter = new_counter();
+        for now in 0..50 {
+            counter.record(&uncapped, now).unwrap();
+        }
+        assert_eq!(counter.count, 50);
+    }
+
+    fn board(scores: &[u64]) -> (Vec<Pubkey>, Vec<LeaderboardEntry>) {
+        let wallets: Vec<Pubkey> = scores.iter().map(|_| Pubkey::new_unique()).collect();
+        let entries = wallets
+            .iter()
+            .zip(scores)
+            .map(|(wallet, score)| LeaderboardEntry {
+                wallet: *wallet,
+                score: *score,
+            })
+            .collect();
+        (wallets, entries)
+    }
+
+    fn scores(entries: &[LeaderboardEntry]) -> Vec<u64> {
+        entries.iter().map(|entry| entry.score).collect()
+    }
+
+    #[test]
+    fn place_keeps_earlier_holder_ahead_on_ties() {
+        let (wallets, mut entries) = board(&[50, 30]);
+        let newcomer = Pubkey::new_unique();
+        assert!(Leaderboard::place(&mut entries, 5, newcomer, 30));
+        assert_eq!(scores(&entries), vec![50, 30, 30]);
+        assert_eq!(entries[1].wallet, wallets[1]);
+        assert_eq!(entries[2].wallet, newcomer);
+    }
+
+    #[test]
+    fn place_on_full_board_drops_the_lowest() {
+        let (wallets, mut entries) = board(&[50, 40, 30]);
+        // Not above the cut: ignored
+        assert!(!Leaderboard::place(&mut entries, 3, Pubkey::new_unique(), 30));
+        assert_eq!(scores(&entries), vec![50, 40, 30]);
+
+        let newcomer = Pubkey::new_unique();
+        assert!(Leaderboard::place(&mut entries, 3, newcomer, 45));
+        assert_eq!(scores(&entries), vec![50, 45, 40]);
+        assert!(entries.iter().all(|entry| entry.wallet != wallets[2]));
+    }
+
+    #[test]
+    fn place_lets_a_dropped_score_climb_back_in() {
+        let (_, mut entries) = board(&[50, 40, 30]);
+        let climber = Pubkey::new_unique();
+        assert!(!Leaderboard::place(&mut entries, 3, climber, 10));
+        assert!(!Leaderboard::place(&mut entries, 3, climber, 25));
+        assert!(Leaderboard::place(&mut entries, 3, climber, 60));
+        assert_eq!(scores(&entries), vec![60, 50, 40]);
+        assert_eq!(entries[0].wallet, climber);
+    }
+
+    #[test]
+    fn place_re_ranks_an_existing_entry() {
+        let (wallets, mut entries) = board(&[50, 40, 30]);
+        assert!(!Leaderboard::place(&mut entries, 3, wallets[2], 30));
+        assert!(Leaderboard::place(&mut entries, 3, wallets[2], 45));
+        assert_eq!(scores(&entries), vec![50, 45, 40]);
+        assert_eq!(entries[1].wallet, wallets[2]);
+        assert_eq!(entries.len(), 3);
+
+        // Falling below others moves it down rather than duplicating it
+        assert!(Leaderboard::place(&mut entries, 3, wallets[0], 35));
+        assert_eq!(scores(&entries), vec![45, 40, 35]);
+        assert_eq!(entries[2].wallet, wallets[0]);
+    }
+
+    #[test]
+    fn evict_removes_only_that_wallet() {
+        let (wallets, mut entries) = board(&[50, 40, 30]);
+        assert!(Leaderboard::evict(&mut entries, wallets[1]));
+        assert_eq!(scores(&entries), vec![50, 30]);
+        assert!(!Leaderboard::evict(&mut entries, wallets[1]));
+    }
+
+    #[test]
+    fn required_signer_rules() {
+        for (user, authority) in [(false, false), (true, false), (false, true), (true, true)] {
+            assert_eq!(RequiredSigner::User.satisfied(user, authority), user);
+            assert_eq!(RequiredSigner::Authority.satisfied(user, authority), authority);
+            assert_eq!(RequiredSigner::Both.satisfied(user, authority), user && authority);
+        }
+    }
 }
@@ -0,0 +1,219 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  airdrop,
+  createProject,
+  dailyStatsPda,
+  ensureProtocolConfig,
+  expectRejected,
+  program,
+  registerUser,
+} from "./fixtures";
+
+// Every attack below is built from accounts that really exist and signers that
+// really sign, so a rejection can only come from the account constraints.
+describe("account validation", () => {
+  const authorityA = Keypair.generate();
+  const authorityB = Keypair.generate();
+  const walletA = Keypair.generate();
+  const walletB = Keypair.generate();
+  const attacker = Keypair.generate();
+
+  let projectA: PublicKey;
+  let projectB: PublicKey;
+  let userA: PublicKey;
+  let userB: PublicKey;
+
+  before(async () => {
+    await Promise.all(
+      [authorityA, authorityB, walletA, walletB, attacker].map((kp) => airdrop(kp.publicKey))
+    );
+
+    await ensureProtocolConfig();
+
+    projectA = await createProject("VALIDATION_A", authorityA);
+    projectB = await createProject("VALIDATION_B", authorityB);
+    userA = await registerUser(projectA, walletA);
+    userB = await registerUser(projectB, walletB);
+  });
+
+  describe("RecordEvent", () => {
+    it("records a login for the user's own project", async () => {
+      await program.methods
+        .recordDailyLogin()
+        .accountsPartial({
+          project: projectA,
+          user: userA,
+          userWallet: walletA.publicKey,
+          activity: null,
+          tierConfig: null,
+          seasonStats: null,
+          dailyStats: await dailyStatsPda(projectA),
+          payer: walletA.publicKey,
+        })
+        .signers([walletA])
+        .rpc();
+
+      const user = await program.account.user.fetch(userA);
+      expect(user.dailyLogins.toNumber()).to.equal(1);
+    });
+
+    it("rejects a user from another project", async () => {
+      await expectRejected(
+        program.methods
+          .recordDailyLogin()
+          .accountsPartial({
+            project: projectA,
+            user: userB,
+            userWallet: walletB.publicKey,
+            activity: null,
+            tierConfig: null,
+            seasonStats: null,
+            dailyStats: await dailyStatsPda(projectA),
+            payer: walletB.publicKey,
+          })
+          .signers([walletB])
+          .rpc(),
+        ["InvalidProject", "ConstraintSeeds"]
+      );
+    });
+
+    it("rejects a quest recorded against another project", async () => {
+      await expectRejected(
+        program.methods
+          .recordQuest("cross-project")
+          .accountsPartial({
+            project: projectB,
+            user: userA,
+            userWallet: walletA.publicKey,
+            activity: null,
+            tierConfig: null,
+            seasonStats: null,
+            dailyStats: await dailyStatsPda(projectB),
+            payer: walletA.publicKey,
+          })
+          .signers([walletA])
+          .rpc(),
+        ["InvalidProject", "ConstraintSeeds"]
+      );
+    });
+
+    it("rejects a referral signed by a wallet that doesn't own the user", async () => {
+      await expectRejected(
+        program.methods
+          .recordReferral(attacker.publicKey)
+          .accountsPartial({
+            event: {
+              project: projectA,
+              user: userA,
+              userWallet: attacker.publicKey,
+              activity: null,
+              tierConfig: null,
+              seasonStats: null,
+              dailyStats: await dailyStatsPda(projectA),
+              payer: attacker.publicKey,
+            },
+          })
+          .signers([attacker])
+          .rpc(),
+        ["InvalidUser", "ConstraintSeeds"]
+      );
+    });
+  });
+
+  describe("UpdateTGEM", () => {
+    it("lets a project authority update its own user", async () => {
+      await program.methods
+        .updateTgemEarned(new BN(100))
+        .accountsPartial({
+          project: projectA,
+          user: userA,
+          userWallet: walletA.publicKey,
+          tierConfig: null,
+          authority: authorityA.publicKey,
+        })
+        .signers([authorityA])
+        .rpc();
+
+      const user = await program.account.user.fetch(userA);
+      expect(user.totalTgemEarned.toNumber()).to.equal(100);
+    });
+
+    it("rejects inflating a user of another project", async () => {
+      await expectRejected(
+        program.methods
+          .updateTgemEarned(new BN(1_000_000))
+          .accountsPartial({
+            project: projectA,
+            user: userB,
+            userWallet: walletB.publicKey,
+            tierConfig: null,
+            authority: authorityA.publicKey,
+          })
+          .signers([authorityA])
+          .rpc(),
+        ["InvalidProject", "ConstraintSeeds"]
+      );
+    });
+
+    it("rejects an authority acting on a project it doesn't own", async () => {
+      await expectRejected(
+        program.methods
+          .updateTgemEarned(new BN(1_000_000))
+          .accountsPartial({
+            project: projectB,
+            user: userB,
+            userWallet: walletB.publicKey,
+            tierConfig: null,
+            authority: authorityA.publicKey,
+          })
+          .signers([authorityA])
+          .rpc(),
+        ["Unauthorized"]
+      );
+    });
+
+    it("rejects a user_wallet that doesn't match the user", async () => {
+      await expectRejected(
+        program.methods
+          .updateTgemEarned(new BN(1))
+          .accountsPartial({
+            project: projectA,
+            user: userA,
+            userWallet: attacker.publicKey,
+            tierConfig: null,
+            authority: authorityA.publicKey,
+          })
+          .signers([authorityA])
+          .rpc(),
+        ["InvalidUser", "ConstraintSeeds"]
+      );
+    });
+
+    it("rejects crediting rewards across projects", async () => {
+      await expectRejected(
+        program.methods
+          .creditRewards(new BN(1_000_000))
+          .accountsPartial({
+            project: projectB,
+            user: userA,
+            userWallet: walletA.publicKey,
+            tierConfig: null,
+            authority: authorityB.publicKey,
+          })
+          .signers([authorityB])
+          .rpc(),
+        ["InvalidProject", "ConstraintSeeds"]
+      );
+    });
+
+    it("leaves the targeted users untouched", async () => {
+      const a = await program.account.user.fetch(userA);
+      const b = await program.account.user.fetch(userB);
+      expect(a.totalTgemEarned.toNumber()).to.equal(100);
+      expect(a.pendingRewards.toNumber()).to.equal(0);
+      expect(b.totalTgemEarned.toNumber()).to.equal(0);
+    });
+  });
+});
@@ -0,0 +1,397 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { keccak_256 } from "@noble/hashes/sha3";
+import { expect } from "chai";
+import {
+  airdrop,
+  clusterTime,
+  createProject,
+  dailyStatsPda,
+  ensureProtocolConfig,
+  eventsOf,
+  expectRejected,
+  newUser,
+  pda,
+  program,
+  provider,
+  recordQuest,
+  Registered,
+  u32,
+  userPda,
+} from "./fixtures";
+
+// Each block runs against its own project so counters start from zero.
+describe("engagement", () => {
+  const authority = Keypair.generate();
+
+  const questHash = (questId: string) => Buffer.from(keccak_256(Buffer.from(questId)));
+
+  before(async () => {
+    await airdrop(authority.publicKey);
+    await ensureProtocolConfig();
+  });
+
+  describe("activity ring buffer", () => {
+    const ACTIVITY_HEADER = 8 + 80;
+    const ACTIVITY_ENTRY = 56;
+
+    it("overwrites the oldest entries once full", async () => {
+      const project = await createProject("ENGAGE_ACTIVITY", authority);
+      await program.methods
+        .updateProjectConfig(null, null, 3)
+        .accountsPartial({ project, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+
+      const user = await newUser(project);
+      const activity = pda(Buffer.from("user_activity"), user.user.toBuffer());
+      await program.methods
+        .initUserActivity()
+        .accountsPartial({ project, user: user.user, activity, payer: provider.wallet.publicKey })
+        .rpc();
+
+      for (let i = 0; i < 5; i++) {
+        await recordQuest(project, user, `quest-${i}`, { activity });
+      }
+
+      const header = await program.account.userActivity.fetch(activity);
+      expect(header.capacity).to.equal(3);
+      expect(header.len).to.equal(3);
+      expect(header.head).to.equal(2);
+
+      // Slots 0 and 1 were overwritten by the 4th and 5th quests; slot 2 is the oldest kept
+      const info = await provider.connection.getAccountInfo(activity);
+      const reference = (slot: number) => {
+        const offset = ACTIVITY_HEADER + slot * ACTIVITY_ENTRY + 16;
+        return info!.data.subarray(offset, offset + 32);
+      };
+      expect(reference(0).equals(questHash("quest-3"))).to.be.true;
+      expect(reference(1).equals(questHash("quest-4"))).to.be.true;
+      expect(reference(2).equals(questHash("quest-2"))).to.be.true;
+
+      // Once the buffer exists, leaving it out can't drop an event from the history
+      await expectRejected(recordQuest(project, user, "quest-5"), ["ActivityAccountRequired"]);
+    });
+
+    it("records events without a buffer", async () => {
+      const project = await createProject("ENGAGE_NO_BUFFER", authority);
+      await program.methods
+        .updateProjectConfig(null, null, 3)
+        .accountsPartial({ project, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+
+      const user = await newUser(project);
+      await recordQuest(project, user, "quest-0");
+      expect((await program.account.user.fetch(user.user)).quests.toNumber()).to.equal(1);
+    });
+  });
+
+  describe("event hash chain", () => {
+    const le64 = (value: BN) => value.toArrayLike(Buffer, "le", 8);
+
+    it("extends the chain as keccak(prev || seq || kind || id || payload || count || timestamp)", async () => {
+      const project = await createProject("ENGAGE_CHAIN", authority);
+      const user = await newUser(project);
+
+      let head = Buffer.alloc(32);
+      for (let i = 1; i <= 2; i++) {
+        const signature = await recordQuest(project, user, `chain-${i}`);
+        const [recorded] = await eventsOf(signature, "EventRecorded");
+
+        head = Buffer.from(
+          keccak_256(
+            Buffer.concat([
+              head,
+              le64(new BN(i)),
+              Buffer.from([1]), // quest
+              Buffer.alloc(2),
+              questHash(`chain-${i}`),
+              le64(new BN(i)),
+              le64(recorded.timestamp as BN),
+            ])
+          )
+        );
+        expect((recorded.eventSeq as BN).toNumber()).to.equal(i);
+        expect(Buffer.from(recorded.eventChainHash as number[]).equals(head)).to.be.true;
+      }
+
+      const account = await program.account.user.fetch(user.user);
+      expect(account.eventSeq.toNumber()).to.equal(2);
+      expect(Buffer.from(account.eventChainHash).equals(head)).to.be.true;
+    });
+  });
+
+  describe("daily stats", () => {
+    it("counts each active user once per day", async () => {
+      const project = await createProject("ENGAGE_DAILY", authority);
+      const first = await newUser(project);
+      const second = await newUser(project);
+
+      await recordQuest(project, first, "daily-1");
+      await recordQuest(project, first, "daily-2");
+      await recordQuest(project, second, "daily-1");
+
+      const stats = await program.account.dailyStats.fetch(await dailyStatsPda(project));
+      expect(stats.newUsers.toNumber()).to.equal(2);
+      expect(stats.activeUsers.toNumber()).to.equal(2);
+      expect(stats.quests.toNumber()).to.equal(3);
+    });
+
+    it("is funded by the payer rather than the registering wallet", async () => {
+      const project = await createProject("ENGAGE_DAILY_PAYER", authority);
+      const wallet = Keypair.generate();
+      await airdrop(wallet.publicKey);
+      const before = await provider.connection.getBalance(wallet.publicKey);
+
+      await program.methods
+        .registerUser()
+        .accountsPartial({
+          project,
+          user: userPda(project, wallet.publicKey),
+          dailyStats: await dailyStatsPda(project),
+          userWallet: wallet.publicKey,
+          payer: provider.wallet.publicKey,
+        })
+        .signers([wallet])
+        .rpc({ commitment: "confirmed" });
+
+      const userRent = (await provider.connection.getAccountInfo(userPda(project, wallet.publicKey)))!.lamports;
+      const after = await provider.connection.getBalance(wallet.publicKey);
+      expect(before - after).to.equal(userRent);
+    });
+  });
+
+  describe("closing users", () => {
+    it("closes a sponsored user whose wallet holds no SOL", async () => {
+      const project = await createProject("ENGAGE_SPONSORED_CLOSE", authority);
+      const rentSponsor = pda(Buffer.from("rent_sponsor"), project.toBuffer());
+      await program.methods
+        .createRentSponsor(new BN(LAMPORTS_PER_SOL))
+        .accountsPartial({ project, rentSponsor, authority: authority.publicKey, payer: provider.wallet.publicKey })
+        .signers([authority])
+        .rpc();
+      await program.methods
+        .fundRentSponsor(new BN(LAMPORTS_PER_SOL / 2))
+        .accountsPartial({ project, rentSponsor, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+
+      const wallet = Keypair.generate();
+      const user = userPda(project, wallet.publicKey);
+      await program.methods
+        .registerUserSponsored()
+        .accountsPartial({
+          project,
+          rentSponsor,
+          user,
+          dailyStats: await dailyStatsPda(project),
+          userWallet: wallet.publicKey,
+        })
+        .signers([wallet])
+        .rpc({ commitment: "confirmed" });
+
+      const userRent = (await provider.connection.getAccountInfo(user))!.lamports;
+      const sponsorBefore = await provider.connection.getBalance(rentSponsor);
+      await program.methods
+        .closeUser()
+        .accountsPartial({
+          project,
+          user,
+          eventCounters: null,
+          activity: null,
+          seasonStats: null,
+          rentPayer: rentSponsor,
+          userWallet: wallet.publicKey,
+        })
+        .signers([wallet])
+        .rpc({ commitment: "confirmed" });
+
+      // The sponsor pays for the tombstone out of the refund; the wallet never needed SOL
+      const closedUser = pda(Buffer.from("closed_user"), project.toBuffer(), wallet.publicKey.toBuffer());
+      const tombstoneRent = (await provider.connection.getAccountInfo(closedUser))!.lamports;
+      expect(await provider.connection.getBalance(wallet.publicKey)).to.equal(0);
+      expect(await provider.connection.getAccountInfo(user)).to.be.null;
+      expect((await program.account.closedUser.fetch(closedUser)).rentPayer.equals(rentSponsor)).to.be.true;
+      expect(await provider.connection.getBalance(rentSponsor)).to.equal(sponsorBefore + userRent - tombstoneRent);
+    });
+  });
+
+  describe("tiers", () => {
+    it("promotes on crossing a boundary and emits TierChanged", async () => {
+      const project = await createProject("ENGAGE_TIERS", authority);
+      const tierConfig = pda(Buffer.from("tier_config"), project.toBuffer());
+      await program.methods
+        .setTierConfig(
+          { dailyLogin: new BN(0), quest: new BN(10), referral: new BN(0), customEvent: new BN(0), tgemBps: 0 },
+          [new BN(15)],
+          [10_000, 15_000]
+        )
+        .accountsPartial({ project, tierConfig, authority: authority.publicKey, payer: provider.wallet.publicKey })
+        .signers([authority])
+        .rpc();
+
+      const user = await newUser(project);
+      let signature = await recordQuest(project, user, "tier-1", { tierConfig });
+      expect(await eventsOf(signature, "TierChanged")).to.be.empty;
+      expect((await program.account.user.fetch(user.user)).tier).to.equal(0);
+
+      signature = await recordQuest(project, user, "tier-2", { tierConfig });
+      const [changed] = await eventsOf(signature, "TierChanged");
+      expect(changed.oldTier).to.equal(0);
+      expect(changed.newTier).to.equal(1);
+      expect((changed.xp as BN).toNumber()).to.equal(20);
+
+      const account = await program.account.user.fetch(user.user);
+      expect(account.tier).to.equal(1);
+      expect(account.xp.toNumber()).to.equal(20);
+    });
+
+    it("stops requiring the config once tiers are disabled", async () => {
+      const project = await createProject("ENGAGE_TIERS_OFF", authority);
+      const tierConfig = pda(Buffer.from("tier_config"), project.toBuffer());
+      await program.methods
+        .setTierConfig(
+          { dailyLogin: new BN(0), quest: new BN(10), referral: new BN(0), customEvent: new BN(0), tgemBps: 0 },
+          [],
+          [10_000]
+        )
+        .accountsPartial({ project, tierConfig, authority: authority.publicKey, payer: provider.wallet.publicKey })
+        .signers([authority])
+        .rpc();
+      await program.methods
+        .disableTiers()
+        .accountsPartial({ project, tierConfig, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+
+      const user = await newUser(project);
+      await recordQuest(project, user, "untiered");
+      expect((await program.account.user.fetch(user.user)).xp.toNumber()).to.equal(0);
+      expect(await provider.connection.getAccountInfo(tierConfig)).to.be.null;
+    });
+  });
+
+  describe("seasons", () => {
+    it("freezes season stats once the season is closed", async () => {
+      const project = await createProject("ENGAGE_SEASON", authority);
+      const season = pda(Buffer.from("season"), project.toBuffer(), u32(1));
+      const now = await clusterTime();
+      await program.methods
+        .startSeason(new BN(now - 60), new BN(now + 3_600))
+        .accountsPartial({ project, season, authority: authority.publicKey, payer: provider.wallet.publicKey })
+        .signers([authority])
+        .rpc();
+
+      const user = await newUser(project);
+      const seasonStats = pda(Buffer.from("season_stats"), project.toBuffer(), u32(1), user.user.toBuffer());
+      await recordQuest(project, user, "season-1", { seasonStats });
+      expect((await program.account.seasonStats.fetch(seasonStats)).quests.toNumber()).to.equal(1);
+
+      await program.methods
+        .closeSeason()
+        .accountsPartial({ project, season, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+      expect((await program.account.season.fetch(season)).closedAt.toNumber()).to.be.greaterThan(0);
+
+      // Lifetime counters keep moving; the closed season's do not
+      await recordQuest(project, user, "season-2", { seasonStats });
+      expect((await program.account.user.fetch(user.user)).quests.toNumber()).to.equal(2);
+      expect((await program.account.seasonStats.fetch(seasonStats)).quests.toNumber()).to.equal(1);
+
+      // Leaving doesn't take the frozen stats with it
+      await expectRejected(
+        program.methods
+          .closeUser()
+          .accountsPartial({
+            project,
+            user: user.user,
+            eventCounters: null,
+            activity: null,
+            seasonStats,
+            rentPayer: provider.wallet.publicKey,
+            userWallet: user.wallet.publicKey,
+          })
+          .signers([user.wallet])
+          .rpc(),
+        ["SeasonNotLive"]
+      );
+
+      // Nor are new stats opened for a season that is over
+      const late = await newUser(project);
+      const lateStats = pda(Buffer.from("season_stats"), project.toBuffer(), u32(1), late.user.toBuffer());
+      await expectRejected(recordQuest(project, late, "season-3", { seasonStats: lateStats }), ["SeasonNotLive"]);
+      expect(await provider.connection.getAccountInfo(lateStats)).to.be.null;
+    });
+  });
+
+  describe("leaderboards", () => {
+    const LEADERBOARD_HEADER = 8 + 48;
+    const LEADERBOARD_ENTRY = 40;
+
+    const standings = async (leaderboard: PublicKey) => {
+      const { len } = await program.account.leaderboard.fetch(leaderboard);
+      const { data } = (await provider.connection.getAccountInfo(leaderboard))!;
+      return Array.from({ length: len }, (_, i) => {
+        const offset = LEADERBOARD_HEADER + i * LEADERBOARD_ENTRY;
+        return {
+          wallet: new PublicKey(data.subarray(offset, offset + 32)).toBase58(),
+          score: Number(data.readBigUInt64LE(offset + 32)),
+        };
+      });
+    };
+
+    it("keeps the top scores in order and drops closed users", async () => {
+      const project = await createProject("ENGAGE_BOARD", authority);
+      const leaderboard = pda(Buffer.from("leaderboard"), project.toBuffer(), Buffer.from([1]), u32(0));
+      await program.methods
+        .createLeaderboard({ quests: {} }, 0, 2)
+        .accountsPartial({ project, leaderboard, authority: authority.publicKey, payer: provider.wallet.publicKey })
+        .signers([authority])
+        .rpc();
+
+      const [low, high, mid] = [await newUser(project), await newUser(project), await newUser(project)];
+      const quests = async (user: Registered, count: number) => {
+        for (let i = 0; i < count; i++) {
+          await recordQuest(project, user, `board-${i}`, { leaderboards: [leaderboard] });
+        }
+      };
+      await quests(low, 1);
+      await quests(high, 3);
+      await quests(mid, 2);
+
+      const wallet = (user: Registered) => user.wallet.publicKey.toBase58();
+      expect(await standings(leaderboard)).to.deep.equal([
+        { wallet: wallet(high), score: 3 },
+        { wallet: wallet(mid), score: 2 },
+      ]);
+
+      await program.methods
+        .closeUser()
+        .accountsPartial({
+          project,
+          user: mid.user,
+          eventCounters: null,
+          activity: null,
+          seasonStats: null,
+          rentPayer: mid.wallet.publicKey,
+          userWallet: mid.wallet.publicKey,
+        })
+        .remainingAccounts([{ pubkey: leaderboard, isSigner: false, isWritable: true }])
+        .signers([mid.wallet])
+        .rpc();
+      expect(await standings(leaderboard)).to.deep.equal([{ wallet: wallet(high), score: 3 }]);
+
+      // A user who fell off the board gets back on by resubmitting
+      await program.methods
+        .submitLeaderboardScore()
+        .accountsPartial({ leaderboard, user: low.user, seasonStats: null })
+        .rpc();
+      expect(await standings(leaderboard)).to.deep.equal([
+        { wallet: wallet(high), score: 3 },
+        { wallet: wallet(low), score: 1 },
+      ]);
+    });
+  });
+});
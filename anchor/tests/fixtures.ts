@@ -0,0 +1,160 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, SYSVAR_CLOCK_PUBKEY } from "@solana/web3.js";
+import { expect } from "chai";
+import { TherasProtocol } from "../target/types/theras_protocol";
+
+// Shared theras_protocol setup; every suite runs against the same local validator.
+export const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+export const program = anchor.workspace.TherasProtocol as Program<TherasProtocol>;
+
+const BPF_LOADER_UPGRADEABLE = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+export const pda = (...seeds: Buffer[]) =>
+  PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+export const u32 = (value: number) => {
+  const buf = Buffer.alloc(4);
+  buf.writeUInt32LE(value);
+  return buf;
+};
+
+export const projectPda = (key: string) => pda(Buffer.from("project"), Buffer.from(key));
+
+export const userPda = (project: PublicKey, wallet: PublicKey) =>
+  pda(Buffer.from("user"), project.toBuffer(), wallet.toBuffer());
+
+/** `Clock::unix_timestamp` as the program sees it, which can lag wall time */
+export const clusterTime = async () => {
+  const clock = await provider.connection.getAccountInfo(SYSVAR_CLOCK_PUBKEY);
+  return Number(clock!.data.readBigInt64LE(32));
+};
+
+export const dailyStatsPda = async (project: PublicKey) => {
+  const day = Buffer.alloc(8);
+  day.writeBigInt64LE(BigInt(Math.floor((await clusterTime()) / 86_400)));
+  return pda(Buffer.from("daily_stats"), project.toBuffer(), day);
+};
+
+export const airdrop = async (to: PublicKey) => {
+  const sig = await provider.connection.requestAirdrop(to, 2 * LAMPORTS_PER_SOL);
+  const latest = await provider.connection.getLatestBlockhash();
+  await provider.connection.confirmTransaction({ signature: sig, ...latest });
+};
+
+/** Suites run in one validator, so only the first one initializes the config */
+export const ensureProtocolConfig = async () => {
+  const protocolConfig = pda(Buffer.from("protocol_config"));
+  if (await program.account.protocolConfig.fetchNullable(protocolConfig)) {
+    return;
+  }
+  const [programData] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE
+  );
+  await program.methods
+    .initializeProtocolConfig({ open: {} }, provider.wallet.publicKey, new BN(0))
+    .accountsPartial({ programData, admin: provider.wallet.publicKey })
+    .rpc();
+};
+
+export const createProject = async (key: string, authority: Keypair) => {
+  const protocolConfig = pda(Buffer.from("protocol_config"));
+  const config = await program.account.protocolConfig.fetch(protocolConfig);
+  const project = projectPda(key);
+  await program.methods
+    .initializeProject(key, true)
+    .accountsPartial({
+      project,
+      protocolConfig,
+      registryPage: pda(Buffer.from("project_registry"), u32(config.projectCount.divn(64).toNumber())),
+      keyAlias: pda(Buffer.from("project_alias"), Buffer.from(key)),
+      creatorAllowance: null,
+      treasury: null,
+      authority: authority.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([authority])
+    .rpc();
+  return project;
+};
+
+export type Registered = { wallet: Keypair; user: PublicKey };
+
+export const registerUser = async (project: PublicKey, wallet: Keypair) => {
+  const user = userPda(project, wallet.publicKey);
+  await program.methods
+    .registerUser()
+    .accountsPartial({
+      project,
+      user,
+      dailyStats: await dailyStatsPda(project),
+      userWallet: wallet.publicKey,
+      payer: provider.wallet.publicKey,
+    })
+    .signers([wallet])
+    .rpc({ commitment: "confirmed" });
+  return user;
+};
+
+/** Register a freshly funded wallet */
+export const newUser = async (project: PublicKey): Promise<Registered> => {
+  const wallet = Keypair.generate();
+  await airdrop(wallet.publicKey);
+  return { wallet, user: await registerUser(project, wallet) };
+};
+
+export type RecordOptions = {
+  activity?: PublicKey;
+  tierConfig?: PublicKey;
+  seasonStats?: PublicKey;
+  leaderboards?: PublicKey[];
+};
+
+export const recordQuest = async (
+  project: PublicKey,
+  { wallet, user }: Registered,
+  questId: string,
+  opts: RecordOptions = {}
+) =>
+  program.methods
+    .recordQuest(questId)
+    .accountsPartial({
+      project,
+      user,
+      userWallet: wallet.publicKey,
+      activity: opts.activity ?? null,
+      tierConfig: opts.tierConfig ?? null,
+      seasonStats: opts.seasonStats ?? null,
+      dailyStats: await dailyStatsPda(project),
+      payer: provider.wallet.publicKey,
+    })
+    .remainingAccounts(
+      (opts.leaderboards ?? []).map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }))
+    )
+    .signers([wallet])
+    .rpc({ commitment: "confirmed" });
+
+/** Events emitted with `emit!` by the transaction, matched by name in any case */
+export const eventsOf = async (signature: string, name: string) => {
+  const tx = await provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const parser = new anchor.EventParser(program.programId, program.coder);
+  return [...parser.parseLogs(tx!.meta!.logMessages!)]
+    .filter((event) => event.name.toLowerCase() === name.toLowerCase())
+    .map((event) => event.data);
+};
+
+export const expectRejected = async (tx: Promise<unknown>, codes: string[]) => {
+  try {
+    await tx;
+  } catch (err) {
+    const code = (err as anchor.AnchorError).error?.errorCode?.code;
+    expect(codes, `unexpected error: ${err}`).to.include(code);
+    return;
+  }
+  expect.fail("transaction should have been rejected");
+};
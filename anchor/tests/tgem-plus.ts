@@ -0,0 +1,105 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  createAccount,
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  transfer,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { TgemPlus } from "../target/types/tgem_plus";
+import { airdrop, createProject, ensureProtocolConfig, newUser, provider, Registered } from "./fixtures";
+
+// Shutdown is irreversible and the TGEM+ state is a singleton, so this suite
+// is the only one that touches tgem_plus and runs its steps in order.
+describe("tgem_plus", () => {
+  const tgem = anchor.workspace.TgemPlus as Program<TgemPlus>;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+  const authority = Keypair.generate();
+  const holder = Keypair.generate();
+
+  const tgemPda = (...seeds: Buffer[]) => PublicKey.findProgramAddressSync(seeds, tgem.programId)[0];
+  const state = tgemPda(Buffer.from("state"));
+  const mint = tgemPda(Buffer.from("mint"));
+
+  let collateralMint: PublicKey;
+  let vault: PublicKey;
+  let project: PublicKey;
+  let earner: Registered;
+
+  const balance = async (account: PublicKey) => Number((await getAccount(provider.connection, account)).amount);
+
+  before(async () => {
+    await Promise.all([authority, holder].map((kp) => airdrop(kp.publicKey)));
+    await ensureProtocolConfig();
+
+    collateralMint = await createMint(provider.connection, payer, payer.publicKey, null, 6);
+    vault = await createAccount(provider.connection, payer, collateralMint, state, Keypair.generate());
+    await tgem.methods
+      .initialize(vault)
+      .accountsPartial({ state, mint, collateralMint, authority: payer.publicKey })
+      .rpc();
+
+    project = await createProject("TGEM_PLUS", authority);
+    await tgem.methods
+      .initializeProjectLedger()
+      .accountsPartial({ state, project, authority: payer.publicKey, payer: payer.publicKey })
+      .rpc();
+    earner = await newUser(project);
+  });
+
+  it("lets a holder who never registered redeem pro-rata after shutdown, even while paused", async () => {
+    const funding = await getOrCreateAssociatedTokenAccount(provider.connection, payer, collateralMint, payer.publicKey);
+    await mintTo(provider.connection, payer, collateralMint, funding.address, payer, 1_000);
+
+    await tgem.methods
+      .mintTgemPlus(new BN(1_000))
+      .accountsPartial({
+        state,
+        mint,
+        project,
+        therasUser: earner.user,
+        userWallet: earner.wallet.publicKey,
+        collateralVault: vault,
+        authorityCollateralAccount: funding.address,
+        authority: payer.publicKey,
+        payer: payer.publicKey,
+      })
+      .rpc();
+
+    // TGEM+ is an ordinary SPL token: pass some to a wallet with no theras_protocol account
+    const earnerTokens = await getOrCreateAssociatedTokenAccount(provider.connection, payer, mint, earner.wallet.publicKey);
+    const holderTokens = await getOrCreateAssociatedTokenAccount(provider.connection, payer, mint, holder.publicKey);
+    await transfer(provider.connection, payer, earnerTokens.address, holderTokens.address, earner.wallet, 400);
+    const holderCollateral = await getOrCreateAssociatedTokenAccount(provider.connection, payer, collateralMint, holder.publicKey);
+
+    // A guardian pause taken before shutdown doesn't trap holders afterwards
+    await tgem.methods
+      .guardianPause({ stablecoinRedemption: {} })
+      .accountsPartial({ state, guardian: payer.publicKey })
+      .rpc();
+    await tgem.methods.triggerShutdown().accountsPartial({ state, collateralVault: vault, authority: payer.publicKey }).rpc();
+
+    await tgem.methods
+      .redeemStablecoin(new BN(400))
+      .accountsPartial({
+        state,
+        mint,
+        userTokenAccount: holderTokens.address,
+        collateralVault: vault,
+        userCollateralAccount: holderCollateral.address,
+        user: holder.publicKey,
+      })
+      .signers([holder])
+      .rpc();
+
+    expect(await balance(holderCollateral.address)).to.equal(400);
+    expect(await balance(holderTokens.address)).to.equal(0);
+    const account = await tgem.account.tgemPlusState.fetch(state);
+    expect(account.totalSupply.toNumber()).to.equal(600);
+    expect(account.stablecoinRedeemed.toNumber()).to.equal(400);
+  });
+});